@@ -1,19 +1,52 @@
+#[cfg(feature = "audio")]
+use crate::audio::SoundPlayer;
+use crate::keymap::{Action, KeyContext, Keymap};
 use crate::persistence::{
-    config::Config,
-    sessions::{Session, SessionHistory},
-    tags::TagStore,
-    tasks::{parse_task_input, TaskStore},
+    archive::{ArchiveReason, ArchiveStore, ArchivedTask},
+    calendar::DayBoundary,
+    config::{self, Config},
+    export::{self, ExportFilter, ExportFormat},
+    sessions::{self, Interruption, Session, SessionHistory, SessionMode, SessionType, TaskShare},
+    state::TimerSnapshot,
+    tags::{self, TagStore},
+    tasks::{
+        add_project, parse_task_input, Subtask, TaskData, TaskEventKind, TaskFlow, TaskInput,
+        TaskQuery, TaskSort, TaskStore,
+    },
+    NewerVersion,
 };
-use chrono::Timelike;
-use crossterm::event::KeyCode;
+use crate::ui::theme::Theme;
+use chrono::{DateTime, NaiveDate, Timelike, Utc};
+use crossterm::event::{KeyCode, KeyEvent};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use std::borrow::Cow;
+use std::ops::{Deref, DerefMut, RangeInclusive};
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 
-/// Timer mode - Pomodoro with auto-cycling or flexible Timer
+/// Interrupted work shorter than this is treated as a misclick and not recorded
+const MIN_INTERRUPTED_SECS: u64 = 60;
+
+/// Bounds and step for custom Timer-mode lengths (1 min – 3 h)
+const MIN_TIMER_SECS: u64 = 60;
+const MAX_TIMER_SECS: u64 = 180 * 60;
+const TIMER_STEP_SECS: u64 = 60;
+
+/// Task-list changes that can be undone
+const UNDO_LIMIT: usize = 50;
+/// Time on a task below this is left out of a session's split (e.g. passing it while scrolling)
+const MIN_SHARE_SECS: u64 = 60;
+
+/// Choices for how long unused tags are kept, in days (0 = forever)
+const TAG_RETENTION_STEPS: [u32; 8] = [0, 7, 14, 30, 60, 90, 180, 365];
+
+/// Timer mode - Pomodoro with auto-cycling, flexible Timer, or count-up Stopwatch
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TimerMode {
     Pomodoro,
     Timer(u64),
+    /// Open-ended flow session; Work counts up, ShortBreak counts down the suggested break
+    Stopwatch,
 }
 
 /// Current state in the Pomodoro cycle
@@ -33,24 +66,76 @@ impl TimerState {
         }
     }
 
-    pub fn color(&self) -> ratatui::style::Color {
-        use ratatui::style::Color;
+    pub fn session_type(&self) -> SessionType {
         match self {
-            TimerState::Work => Color::Cyan,
-            TimerState::ShortBreak => Color::Green,
-            TimerState::LongBreak => Color::Magenta,
+            TimerState::Work => SessionType::Work,
+            TimerState::ShortBreak => SessionType::ShortBreak,
+            TimerState::LongBreak => SessionType::LongBreak,
         }
     }
 
-    pub fn session_type(&self) -> &'static str {
-        match self {
-            TimerState::Work => "work",
-            TimerState::ShortBreak => "short_break",
-            TimerState::LongBreak => "long_break",
+    pub fn from_session_type(session_type: &str) -> Option<Self> {
+        match SessionType::parse(session_type)? {
+            SessionType::Work => Some(TimerState::Work),
+            SessionType::ShortBreak => Some(TimerState::ShortBreak),
+            SessionType::LongBreak => Some(TimerState::LongBreak),
+        }
+    }
+}
+
+/// A finished work session waiting for its note, and for the user to confirm who gets the credit
+#[derive(Debug, Clone)]
+pub struct PendingSession {
+    pub session: Session,
+    /// Where the session's time went, most first
+    pub shares: Vec<TaskShare>,
+    /// The share credited with the session (and its pomodoro); None credits no task
+    pub credited: Option<usize>,
+    /// Stopwatch, custom-timer and interrupted sessions are recorded without crediting a pomodoro
+    pub earns_pomodoro: bool,
+}
+
+impl PendingSession {
+    /// Credit the next task in the split, then no task, then the first again
+    pub fn reassign(&mut self) {
+        self.credited = match self.credited {
+            Some(i) if i + 1 < self.shares.len() => Some(i + 1),
+            Some(_) => None,
+            None => (!self.shares.is_empty()).then_some(0),
+        };
+    }
+
+    pub fn credited_share(&self) -> Option<&TaskShare> {
+        self.credited.and_then(|i| self.shares.get(i))
+    }
+
+    /// The session linked to the credited task, with the split attached if there was one
+    fn into_session(self) -> (Session, Option<TaskShare>) {
+        let credited = self.credited_share().cloned();
+        let mut session = self.session;
+        match &credited {
+            Some(share) => session.link_task(share.task_id, &share.task_name, &share.task_tags),
+            None => {
+                session.task_id = None;
+                session.task_name = None;
+                session.task_tags.clear();
+            }
+        }
+        if self.shares.len() > 1 {
+            session.shares = self.shares;
         }
+        (session, credited)
     }
 }
 
+/// A phase that ran out while the app was closed
+#[derive(Debug, Clone)]
+pub struct MissedPhase {
+    pub state: TimerState,
+    pub duration_secs: u64,
+    pub ended_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ActivePane {
     Tasks,
@@ -69,9 +154,21 @@ pub enum InputMode {
     Normal,
     AddingTask,
     EditingTask,
+    AddingSubtask,
+    EditingSubtask,
     QuickCapture,
     SessionNote,
     ConfirmReset,
+    ConfirmResume,
+    SettingDuration,
+    PickingProfile,
+    PickingProject,
+    NamingProject,
+    BrowsingArchive,
+    SearchingTasks,
+    Exporting,
+    ManagingTags,
+    RenamingTag,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -85,8 +182,6 @@ pub enum SettingsCategory {
 }
 
 impl SettingsCategory {
-
-
     pub fn name(&self) -> &'static str {
         match self {
             Self::Timer => "TIMER",
@@ -102,6 +197,7 @@ impl SettingsCategory {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SettingsField {
     // Timer
+    Profile,
     WorkDuration,
     ShortBreak,
     LongBreak,
@@ -109,14 +205,20 @@ pub enum SettingsField {
     // Goals
     DailyGoal,
     ShowStreak,
+    StreakRule,
+    DayStartHour,
     // Appearance
+    Theme,
     BreathingAnimation,
     HideHintsAfter,
     // Behavior
     AutoStartBreaks,
     FocusModeOnStart,
+    TagRetention,
     // Notifications
     NotificationsEnabled,
+    SoundEnabled,
+    SoundVolume,
     // Danger
     ResetData,
 }
@@ -124,30 +226,47 @@ pub enum SettingsField {
 impl SettingsField {
     pub fn category(&self) -> SettingsCategory {
         match self {
-            Self::WorkDuration | Self::ShortBreak | Self::LongBreak | Self::SessionsBeforeLong => {
-                SettingsCategory::Timer
+            Self::Profile
+            | Self::WorkDuration
+            | Self::ShortBreak
+            | Self::LongBreak
+            | Self::SessionsBeforeLong => SettingsCategory::Timer,
+            Self::DailyGoal | Self::ShowStreak | Self::StreakRule | Self::DayStartHour => {
+                SettingsCategory::Goals
+            }
+            Self::Theme | Self::BreathingAnimation | Self::HideHintsAfter => {
+                SettingsCategory::Appearance
+            }
+            Self::AutoStartBreaks | Self::FocusModeOnStart | Self::TagRetention => {
+                SettingsCategory::Behavior
+            }
+            Self::NotificationsEnabled | Self::SoundEnabled | Self::SoundVolume => {
+                SettingsCategory::Notifications
             }
-            Self::DailyGoal | Self::ShowStreak => SettingsCategory::Goals,
-            Self::BreathingAnimation | Self::HideHintsAfter => SettingsCategory::Appearance,
-            Self::AutoStartBreaks | Self::FocusModeOnStart => SettingsCategory::Behavior,
-            Self::NotificationsEnabled => SettingsCategory::Notifications,
             Self::ResetData => SettingsCategory::Danger,
         }
     }
 
     pub fn all() -> Vec<Self> {
         vec![
+            Self::Profile,
             Self::WorkDuration,
             Self::ShortBreak,
             Self::LongBreak,
             Self::SessionsBeforeLong,
             Self::DailyGoal,
             Self::ShowStreak,
+            Self::StreakRule,
+            Self::DayStartHour,
+            Self::Theme,
             Self::BreathingAnimation,
             Self::HideHintsAfter,
             Self::AutoStartBreaks,
             Self::FocusModeOnStart,
+            Self::TagRetention,
             Self::NotificationsEnabled,
+            Self::SoundEnabled,
+            Self::SoundVolume,
             Self::ResetData,
         ]
     }
@@ -165,26 +284,89 @@ impl SettingsField {
     }
 }
 
-/// A task item
+/// A task item: the saved task plus what the task pane does with it
 #[derive(Debug, Clone)]
-pub struct Task {
-    pub id: Uuid,
-    pub name: String,
-    pub completed: bool,
-    pub pomodoros_spent: u32,
-    pub tags: Vec<String>,
+pub struct Task(TaskData);
+
+impl Deref for Task {
+    type Target = TaskData;
+
+    fn deref(&self) -> &TaskData {
+        &self.0
+    }
+}
+
+impl DerefMut for Task {
+    fn deref_mut(&mut self) -> &mut TaskData {
+        &mut self.0
+    }
 }
 
 impl Task {
+    pub fn from_input(input: TaskInput, project: &str) -> Self {
+        let mut task = Self(TaskData::new(String::new(), project));
+        task.apply_input(input);
+        task
+    }
 
+    /// The task as it would be typed into the prompt
+    pub fn to_input(&self) -> TaskInput {
+        TaskInput {
+            name: self.name.clone(),
+            tags: self.tags.clone(),
+            priority: Some(self.priority),
+            estimate: self.estimate,
+            due: self.due,
+            description: self.description.clone(),
+        }
+    }
 
-    pub fn with_tags(name: String, tags: Vec<String>) -> Self {
-        Self {
-            id: Uuid::new_v4(),
-            name,
-            completed: false,
-            pomodoros_spent: 0,
-            tags,
+    /// Pomodoros spent, against the estimate if there is one: "3/5 🍅" or "🍅×3"
+    pub fn pomodoro_label(&self) -> Option<String> {
+        match self.estimate {
+            Some(estimate) => Some(format!("{}/{} 🍅", self.total_pomodoros(), estimate)),
+            None if self.total_pomodoros() > 0 => Some(format!("🍅×{}", self.total_pomodoros())),
+            None => None,
+        }
+    }
+
+    /// Still open and past its due date
+    pub fn is_overdue(&self, today: NaiveDate) -> bool {
+        !self.completed && self.due.is_some_and(|due| due < today)
+    }
+}
+
+impl From<TaskData> for Task {
+    fn from(data: TaskData) -> Self {
+        Self(data)
+    }
+}
+
+impl Task {
+    /// The task as it is saved
+    pub fn to_data(&self) -> TaskData {
+        self.0.clone()
+    }
+}
+
+/// The task list and archive as they were before a change
+#[derive(Debug, Clone)]
+struct TaskListSnapshot {
+    tasks: Vec<Task>,
+    archive: Vec<ArchivedTask>,
+    selected: (usize, Option<usize>),
+}
+
+impl TaskListSnapshot {
+    /// Credit a pomodoro to the task wherever it is in this snapshot, even in the archive
+    fn credit_pomodoro(&mut self, task_id: Uuid, subtask_id: Option<Uuid>) {
+        let mut tasks = self
+            .tasks
+            .iter_mut()
+            .map(|task| &mut task.0)
+            .chain(self.archive.iter_mut().map(|entry| &mut entry.task));
+        if let Some(task) = tasks.find(|task| task.id == task_id) {
+            task.spend_pomodoro(subtask_id);
         }
     }
 }
@@ -197,9 +379,14 @@ pub struct App {
     pub remaining_time: Duration,
     pub is_paused: bool,
 
+    // Stopwatch
+    pub elapsed_time: Duration,
+    pub flow_break: Duration,
+
     // Drift correction
     start_instant: Option<Instant>,
     start_remaining: Duration,
+    start_elapsed: Duration,
 
     // Pomodoro cycle tracking
     pub session_count: u8,
@@ -208,6 +395,20 @@ pub struct App {
     // Task management
     pub tasks: Vec<Task>,
     pub selected_task_index: usize,
+    /// Set when a subtask of the selected task is selected instead of the task
+    pub selected_subtask: Option<usize>,
+    /// Cleared and deleted tasks
+    pub archive: ArchiveStore,
+    pub archive_cursor: usize,
+    undo_stack: Vec<TaskListSnapshot>,
+    redo_stack: Vec<TaskListSnapshot>,
+    /// Task lists; only the active one is shown in the task pane
+    pub projects: Vec<String>,
+    pub active_project: String,
+    pub project_cursor: usize,
+    /// Search typed into the task pane (see `TaskQuery`); empty shows every task
+    pub task_query: String,
+    pub task_sort: TaskSort,
 
     // Navigation
     pub active_pane: ActivePane,
@@ -228,13 +429,23 @@ pub struct App {
     // Settings
     pub selected_setting: SettingsField,
     pub config: Config,
+    pub theme: Theme,
+    pub keymap: Keymap,
 
     // Session history
     pub session_history: SessionHistory,
+    pub profile_filter: Option<String>, // dashboard shows only this profile
+    pub profile_cursor: usize,
+
+    // Session export (dashboard)
+    pub export_format: ExportFormat,
+    pub export_result: Option<Result<String, String>>,
 
     // Tag autocomplete
     pub tag_store: TagStore,
     pub tag_suggestion: Option<String>,
+    /// Row selected in the tag manager (see `tag_names`)
+    pub tag_cursor: usize,
 
     // Celebration state
     pub show_celebration: bool,
@@ -242,33 +453,128 @@ pub struct App {
     pub celebration_timer: u8,
 
     // Session note (pending session waiting for note)
-    pub pending_session: Option<(String, u64, Option<String>)>, // (type, duration, task_name)
+    pub pending_session: Option<PendingSession>,
+    /// Time spent on each (task, subtask) so far in the current work phase
+    focus_shares: Vec<(Uuid, Option<Uuid>, Duration)>,
+    /// How much of the current work phase `focus_shares` accounts for
+    attributed: Duration,
+
+    // Restored timer state (phases that finished while the app was closed)
+    pub missed_phases: Vec<MissedPhase>,
+    resume_snapshot: Option<TimerSnapshot>,
+    snapshot_counter: u8,
+    /// state.json came from a newer pomo-tui, so it is never overwritten
+    keep_snapshot_file: bool,
+
+    // Phase transition sounds
+    #[cfg(feature = "audio")]
+    sound_player: SoundPlayer,
 
     // Control flags
     pub should_quit: bool,
     pub show_help: bool,
+    /// Problems reading saved data, shown in a banner until the next key press
+    pub storage_errors: Vec<String>,
     needs_save: bool,
 }
 
 impl App {
-    pub fn new() -> Self {
-        let config = Config::load().unwrap_or_default();
-        let session_history = SessionHistory::load().unwrap_or_default();
-        let tag_store = TagStore::load().unwrap_or_default();
+    /// Load everything from disk; fails only if a file was written by a newer pomo-tui
+    pub fn new() -> anyhow::Result<Self> {
+        // Damaged files are quarantined on load; say so instead of quietly starting empty.
+        // Files from a newer version can't be read, and starting without them would overwrite them.
+        let mut storage_errors = Vec::new();
+        let mut newer = None;
+        let mut report = |error: anyhow::Error| {
+            if error.is::<NewerVersion>() {
+                newer.get_or_insert(error);
+            } else {
+                storage_errors.push(format!("{:#}", error));
+            }
+        };
 
-        let task_store = TaskStore::load().unwrap_or_default();
-        let tasks: Vec<Task> = task_store
+        let config = Config::load().unwrap_or_else(|e| {
+            report(e);
+            Config::default()
+        });
+        let mut session_history = SessionHistory::load().unwrap_or_else(|e| {
+            report(e);
+            SessionHistory::default()
+        });
+        session_history.use_config(&config);
+        let mut tag_store = TagStore::load().unwrap_or_else(|e| {
+            report(e);
+            TagStore::default()
+        });
+
+        let archive = ArchiveStore::load().unwrap_or_else(|e| {
+            report(e);
+            ArchiveStore::default()
+        });
+
+        let task_store = TaskStore::load().unwrap_or_else(|e| {
+            report(e);
+            TaskStore::default()
+        });
+        let mut keymap = Keymap::load().unwrap_or_else(|e| {
+            report(e);
+            Keymap::default()
+        });
+        let theme = Theme::load(&config.theme).unwrap_or_else(|e| {
+            report(e);
+            Theme::default()
+        });
+        if let Some(error) = newer {
+            return Err(error);
+        }
+        storage_errors.extend(session_history.load_warning.take());
+        storage_errors.extend(keymap.load_warning.take());
+
+        // Older sessions only name their task; link them to it where the name is unambiguous
+        let known: Vec<TaskData> = task_store
             .tasks
-            .into_iter()
-            .map(|t| Task {
-                id: t.id,
-                name: t.name,
-                completed: t.completed,
-                pomodoros_spent: t.pomodoros_spent,
-                tags: t.tags,
-            })
+            .iter()
+            .chain(archive.entries.iter().map(|entry| &entry.task))
+            .cloned()
             .collect();
+        if session_history.link_tasks(&known) > 0 {
+            let _ = session_history.save();
+        }
+        let in_use: Vec<String> = known.iter().flat_map(|t| t.tags.iter().cloned()).collect();
+        if tag_store.forget_idle(config.tag_retention_days, &in_use) {
+            let _ = tag_store.save();
+        }
 
+        let mut app = Self::from_parts(
+            config,
+            session_history,
+            tag_store,
+            archive,
+            task_store,
+            keymap,
+            theme,
+        );
+        app.storage_errors = storage_errors;
+        app.restore_timer_state();
+        app.clamp_selection();
+        Ok(app)
+    }
+
+    /// A paused Pomodoro timer over data that is already loaded
+    fn from_parts(
+        config: Config,
+        session_history: SessionHistory,
+        tag_store: TagStore,
+        archive: ArchiveStore,
+        task_store: TaskStore,
+        keymap: Keymap,
+        theme: Theme,
+    ) -> Self {
+        let projects = task_store.projects;
+        let active_project = task_store.active_project;
+        let tasks: Vec<Task> = task_store.tasks.into_iter().map(Task::from).collect();
+
+        let task_sort = TaskSort::from_name(&config.task_sort).unwrap_or_default();
         let sessions_before_long = config.sessions_before_long_break;
         let work_duration = Duration::from_secs(config.work_duration_mins * 60);
 
@@ -278,14 +584,28 @@ impl App {
             remaining_time: work_duration,
             is_paused: true,
 
+            elapsed_time: Duration::ZERO,
+            flow_break: Duration::ZERO,
+
             start_instant: None,
             start_remaining: work_duration,
+            start_elapsed: Duration::ZERO,
 
             session_count: 0,
             sessions_before_long,
 
             tasks,
             selected_task_index: 0,
+            selected_subtask: None,
+            projects,
+            active_project,
+            project_cursor: 0,
+            task_query: String::new(),
+            task_sort,
+            archive,
+            archive_cursor: 0,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
 
             active_pane: ActivePane::Tasks,
             current_view: CurrentView::Timer,
@@ -297,24 +617,229 @@ impl App {
             hints_visible: true,
             hint_fade_counter: 0,
 
-            selected_setting: SettingsField::WorkDuration,
+            selected_setting: SettingsField::Profile,
             config,
+            theme,
+            keymap,
 
             session_history,
+            profile_filter: None,
+            profile_cursor: 0,
+            export_format: ExportFormat::Csv,
+            export_result: None,
             tag_store,
             tag_suggestion: None,
+            tag_cursor: 0,
 
             show_celebration: false,
             celebration_message: String::new(),
             celebration_timer: 0,
             pending_session: None,
+            focus_shares: Vec::new(),
+            attributed: Duration::ZERO,
+
+            missed_phases: Vec::new(),
+            resume_snapshot: None,
+            snapshot_counter: 0,
+            keep_snapshot_file: false,
+
+            #[cfg(feature = "audio")]
+            sound_player: SoundPlayer::new(),
 
             should_quit: false,
             show_help: false,
+            storage_errors: Vec::new(),
             needs_save: false,
         }
     }
 
+    /// Resume the timer from the last snapshot, catching up on wall-clock time
+    fn restore_timer_state(&mut self) {
+        let snapshot = match TimerSnapshot::load() {
+            Ok(Some(snapshot)) => snapshot,
+            Ok(None) => return,
+            Err(error) => {
+                self.keep_snapshot_file = error.is::<NewerVersion>();
+                self.storage_errors.push(format!("{:#}", error));
+                return;
+            }
+        };
+        self.catch_up(snapshot, Utc::now());
+    }
+
+    /// Apply a snapshot and run the timer on to `now`, queueing the phases that ended meanwhile
+    fn catch_up(&mut self, snapshot: TimerSnapshot, now: DateTime<Utc>) {
+        self.apply_snapshot(&snapshot);
+
+        if !snapshot.is_running {
+            return;
+        }
+
+        let mut away = (now - snapshot.saved_at).to_std().unwrap_or_default();
+
+        // A stopwatch never runs out, it just kept counting
+        if self.is_counting_up() {
+            self.elapsed_time += away;
+            self.start_elapsed = self.elapsed_time;
+            self.start_instant = Some(Instant::now());
+            self.is_paused = false;
+            return;
+        }
+
+        let mut ended_at = snapshot.saved_at;
+        let mut running = true;
+
+        // Finish every phase that ran out while we were closed
+        while away >= self.remaining_time {
+            away -= self.remaining_time;
+            ended_at += chrono::Duration::from_std(self.remaining_time).unwrap_or_default();
+            self.missed_phases.push(MissedPhase {
+                state: self.timer_state,
+                duration_secs: self.get_current_duration().as_secs(),
+                ended_at,
+            });
+
+            if self.timer_mode != TimerMode::Pomodoro {
+                // A finished flow break hands back to the stopwatch
+                if self.timer_mode == TimerMode::Stopwatch {
+                    self.timer_state = TimerState::Work;
+                }
+                self.remaining_time = self.get_current_duration();
+                self.start_remaining = self.remaining_time;
+                running = false;
+                break;
+            }
+
+            self.next_pomodoro_state();
+            self.remaining_time = self.duration_for_state(self.timer_state);
+            self.start_remaining = self.remaining_time;
+
+            // Breaks may auto-start, but work always waits for the user
+            if !self.config.auto_start_breaks || self.timer_state == TimerState::Work {
+                running = false;
+                break;
+            }
+        }
+
+        if running {
+            self.remaining_time = self.remaining_time.saturating_sub(away);
+            self.start_remaining = self.remaining_time;
+            self.start_instant = Some(Instant::now());
+            self.is_paused = false;
+        }
+
+        if !self.missed_phases.is_empty() {
+            self.resume_snapshot = Some(snapshot);
+            self.input_mode = InputMode::ConfirmResume;
+        }
+    }
+
+    /// Put the timer back exactly as the snapshot describes it (paused)
+    fn apply_snapshot(&mut self, snapshot: &TimerSnapshot) {
+        self.timer_mode = match snapshot.timer_mode.as_str() {
+            "timer" => TimerMode::Timer(snapshot.timer_secs),
+            "stopwatch" => TimerMode::Stopwatch,
+            _ => TimerMode::Pomodoro,
+        };
+        if self.timer_mode == TimerMode::Stopwatch {
+            self.flow_break = Duration::from_secs(snapshot.timer_secs);
+        }
+        self.timer_state =
+            TimerState::from_session_type(&snapshot.timer_state).unwrap_or(TimerState::Work);
+        self.session_count = snapshot
+            .session_count
+            .min(self.sessions_before_long.saturating_sub(1));
+        self.remaining_time =
+            Duration::from_secs(snapshot.remaining_secs).min(self.get_current_duration());
+        self.start_remaining = self.remaining_time;
+        // remaining_secs holds the time counted so far for a running stopwatch
+        self.elapsed_time = if self.is_counting_up() {
+            Duration::from_secs(snapshot.remaining_secs)
+        } else {
+            Duration::ZERO
+        };
+        self.start_elapsed = self.elapsed_time;
+        self.start_instant = None;
+        self.is_paused = true;
+
+        if let Some(id) = snapshot.selected_task {
+            if let Some(index) = self.tasks.iter().position(|t| t.id == id) {
+                self.selected_task_index = index;
+            }
+        }
+    }
+
+    /// Snapshot the timer so it can be resumed after a restart
+    fn save_timer_state(&mut self) {
+        // Keep the old snapshot until the user decides what to do with missed phases
+        if self.keep_snapshot_file || !self.missed_phases.is_empty() {
+            return;
+        }
+
+        self.update_remaining_time();
+        let (timer_mode, timer_secs) = match self.timer_mode {
+            TimerMode::Pomodoro => ("pomodoro", 0),
+            TimerMode::Timer(secs) => ("timer", secs),
+            TimerMode::Stopwatch => ("stopwatch", self.flow_break.as_secs()),
+        };
+
+        let snapshot = TimerSnapshot {
+            saved_at: Utc::now(),
+            timer_mode: timer_mode.to_string(),
+            timer_secs,
+            timer_state: self.timer_state.session_type().as_str().to_string(),
+            remaining_secs: if self.is_counting_up() {
+                self.elapsed_time.as_secs()
+            } else {
+                self.remaining_time.as_secs()
+            },
+            session_count: self.session_count,
+            is_running: !self.is_paused,
+            selected_task: self.selected_task().map(|t| t.id),
+        };
+        let _ = snapshot.save();
+    }
+
+    /// Handle the "phases finished while you were away" prompt
+    fn handle_resume_key(&mut self, key: KeyCode) {
+        match key {
+            // Record the missed phases as sessions
+            KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                for phase in std::mem::take(&mut self.missed_phases) {
+                    let mut session = self.task_session(Session::new(
+                        phase.state.session_type(),
+                        phase.duration_secs,
+                        None,
+                    ));
+                    session.timestamp = phase.ended_at;
+                    self.add_session(session);
+
+                    // Like a finished timer, only Pomodoro-mode work earns a pomodoro
+                    let credit = (phase.state, self.timer_mode, self.selected_ids());
+                    if let (TimerState::Work, TimerMode::Pomodoro, Some((task, subtask))) = credit {
+                        self.credit_pomodoro(task, subtask);
+                    }
+                }
+                let _ = self.session_history.save();
+                self.resume_snapshot = None;
+                self.input_mode = InputMode::Normal;
+            }
+            // Throw them away and restart the interrupted phase
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+                self.missed_phases.clear();
+                if let Some(snapshot) = self.resume_snapshot.take() {
+                    self.apply_snapshot(&snapshot);
+                    // Whatever was focused before quitting still counts, as an abandoned session
+                    self.record_interrupted_session(Interruption::Abandoned, snapshot.saved_at);
+                    self.remaining_time = self.get_current_duration();
+                    self.start_remaining = self.remaining_time;
+                }
+                self.input_mode = InputMode::Normal;
+            }
+            _ => {}
+        }
+    }
+
     fn duration_for_state(&self, state: TimerState) -> Duration {
         match state {
             TimerState::Work => Duration::from_secs(self.config.work_duration_mins * 60),
@@ -323,13 +848,22 @@ impl App {
         }
     }
 
-    pub fn handle_key(&mut self, key: KeyCode) {
+    pub fn handle_key(&mut self, key: KeyEvent) {
         // Reset hint fade counter on any key
         self.hints_visible = true;
         self.hint_fade_counter = 0;
 
+        // The first key after a storage error just dismisses the banner
+        if !self.storage_errors.is_empty() {
+            self.storage_errors.clear();
+            return;
+        }
+
         // Quick capture works anywhere (except when already in input mode)
-        if key == KeyCode::Char('/') && self.input_mode == InputMode::Normal && !self.show_help {
+        if self.input_mode == InputMode::Normal
+            && !self.show_help
+            && self.keymap.action(self.key_context(), key) == Some(Action::QuickCapture)
+        {
             self.input_mode = InputMode::QuickCapture;
             self.input_buffer.clear();
             return;
@@ -342,149 +876,258 @@ impl App {
         }
     }
 
-    fn handle_timer_view_key(&mut self, key: KeyCode) {
+    /// Keymap section for the current view
+    pub fn key_context(&self) -> KeyContext {
+        match self.current_view {
+            CurrentView::Timer => KeyContext::Timer,
+            CurrentView::Dashboard => KeyContext::Dashboard,
+            CurrentView::Settings => KeyContext::Settings,
+        }
+    }
+
+    /// Actions shared by every view
+    fn handle_global_action(&mut self, action: Action) {
+        match action {
+            Action::Quit => {
+                self.save_all();
+                self.should_quit = true;
+            }
+            Action::SwitchView(n) => {
+                self.current_view = match n {
+                    2 => CurrentView::Dashboard,
+                    3 => CurrentView::Settings,
+                    _ => CurrentView::Timer,
+                };
+                self.focus_mode = false;
+            }
+            Action::Back => self.current_view = CurrentView::Timer,
+            _ => {}
+        }
+    }
+
+    fn handle_timer_view_key(&mut self, key: KeyEvent) {
         match self.input_mode {
             InputMode::Normal => self.handle_normal_key(key),
-            InputMode::AddingTask | InputMode::EditingTask | InputMode::QuickCapture => self.handle_input_key(key),
-            InputMode::SessionNote => self.handle_session_note_key(key),
+            InputMode::AddingTask | InputMode::EditingTask | InputMode::QuickCapture => {
+                self.handle_input_key(key.code)
+            }
+            InputMode::AddingSubtask | InputMode::EditingSubtask => {
+                self.handle_subtask_input_key(key.code)
+            }
+            InputMode::SessionNote => self.handle_session_note_key(key.code),
             InputMode::ConfirmReset => {
                 self.input_mode = InputMode::Normal;
             }
+            InputMode::ConfirmResume => self.handle_resume_key(key.code),
+            InputMode::SettingDuration => self.handle_duration_key(key.code),
+            InputMode::PickingProfile => self.handle_profile_key(key.code),
+            InputMode::PickingProject => self.handle_project_key(key.code),
+            InputMode::NamingProject => self.handle_project_name_key(key.code),
+            InputMode::BrowsingArchive => self.handle_archive_key(key.code),
+            InputMode::SearchingTasks => self.handle_search_key(key.code),
+            InputMode::Exporting => self.handle_export_key(key.code),
+            InputMode::ManagingTags => self.handle_tag_manager_key(key.code),
+            InputMode::RenamingTag => self.handle_tag_rename_key(key.code),
         }
     }
 
-    fn handle_normal_key(&mut self, key: KeyCode) {
+    fn handle_normal_key(&mut self, key: KeyEvent) {
         if self.show_help {
             self.show_help = false;
             return;
         }
 
-        match key {
-            KeyCode::Char('q') | KeyCode::Char('Q') => {
-                self.save_all();
-                self.should_quit = true;
-            }
+        let Some(action) = self.keymap.action(KeyContext::Timer, key) else {
+            return;
+        };
+        let task_focus = self.active_pane == ActivePane::Tasks || self.focus_mode;
 
-            KeyCode::Char('?') => {
+        match action {
+            Action::Help => {
                 self.show_help = true;
             }
 
             // Focus mode toggle
-            KeyCode::Char('f') | KeyCode::Char('F') => {
+            Action::ToggleFocus => {
                 self.focus_mode = !self.focus_mode;
             }
 
-            // View switching
-            KeyCode::Char('1') => {
-                self.current_view = CurrentView::Timer;
-                self.focus_mode = false;
+            Action::ToggleTimer => self.toggle_pause(),
+            Action::ResetTimer => self.reset_timer(),
+            Action::Skip => self.skip_to_next(),
+            Action::ToggleMode => self.toggle_mode(),
+
+            Action::SetDuration => {
+                self.input_mode = InputMode::SettingDuration;
+                self.input_buffer.clear();
             }
-            KeyCode::Char('2') => {
-                self.current_view = CurrentView::Dashboard;
-                self.focus_mode = false;
+            Action::PickProfile => {
+                self.profile_cursor = self
+                    .config
+                    .profiles
+                    .iter()
+                    .position(|p| p.name == self.config.active_profile)
+                    .unwrap_or(0);
+                self.input_mode = InputMode::PickingProfile;
             }
-            KeyCode::Char('3') => {
-                self.current_view = CurrentView::Settings;
-                self.focus_mode = false;
+            Action::PickProject => {
+                self.project_cursor = self
+                    .projects
+                    .iter()
+                    .position(|p| *p == self.active_project)
+                    .unwrap_or(0);
+                self.input_mode = InputMode::PickingProject;
             }
+            Action::Increase => self.adjust_timer_length(TIMER_STEP_SECS as i64),
+            Action::Decrease => self.adjust_timer_length(-(TIMER_STEP_SECS as i64)),
 
-            KeyCode::Char(' ') => self.toggle_pause(),
-            KeyCode::Char('r') | KeyCode::Char('R') => self.reset_timer(),
-            KeyCode::Char('n') | KeyCode::Char('N') => self.skip_to_next(),
-            KeyCode::Char('m') | KeyCode::Char('M') => self.toggle_mode(),
+            Action::SwitchPane if !self.focus_mode => {
+                self.active_pane = match self.active_pane {
+                    ActivePane::Tasks => ActivePane::Timer,
+                    ActivePane::Timer => ActivePane::Tasks,
+                };
+            }
 
-            KeyCode::Tab => {
-                if !self.focus_mode {
-                    self.active_pane = match self.active_pane {
-                        ActivePane::Tasks => ActivePane::Timer,
-                        ActivePane::Timer => ActivePane::Tasks,
-                    };
-                }
+            Action::Up
+                if self.active_pane == ActivePane::Tasks && self.selected_task().is_some() =>
+            {
+                self.move_selection(-1)
+            }
+            Action::Down
+                if self.active_pane == ActivePane::Tasks && self.selected_task().is_some() =>
+            {
+                self.move_selection(1)
             }
 
-            KeyCode::Char('k') | KeyCode::Up => {
-                if self.active_pane == ActivePane::Tasks && !self.tasks.is_empty() {
-                    if self.selected_task_index > 0 {
-                        self.selected_task_index -= 1;
-                    } else {
-                        self.selected_task_index = self.tasks.len() - 1;
-                    }
+            Action::Expand if task_focus && self.selected_task().is_some() => {
+                let task = &mut self.tasks[self.selected_task_index];
+                if !task.subtasks.is_empty() && !task.expanded {
+                    task.expanded = true;
+                    self.needs_save = true;
                 }
             }
 
-            KeyCode::Char('j') | KeyCode::Down => {
-                if self.active_pane == ActivePane::Tasks && !self.tasks.is_empty() {
-                    if self.selected_task_index < self.tasks.len() - 1 {
-                        self.selected_task_index += 1;
-                    } else {
-                        self.selected_task_index = 0;
-                    }
+            Action::Collapse if task_focus && self.selected_task().is_some() => {
+                let task = &mut self.tasks[self.selected_task_index];
+                if task.expanded {
+                    task.expanded = false;
+                    self.selected_subtask = None;
+                    self.needs_save = true;
                 }
             }
 
-            KeyCode::Char('a') | KeyCode::Char('A') => {
-                if self.active_pane == ActivePane::Tasks || self.focus_mode {
-                    self.input_mode = InputMode::AddingTask;
-                    self.input_buffer.clear();
-                }
+            Action::AddSubtask if task_focus && self.selected_task().is_some() => {
+                self.input_mode = InputMode::AddingSubtask;
+                self.input_buffer.clear();
             }
 
-            KeyCode::Char('d') | KeyCode::Char('D') => {
-                if (self.active_pane == ActivePane::Tasks || self.focus_mode) && !self.tasks.is_empty() {
-                    self.tasks.remove(self.selected_task_index);
-                    // Clamp index to valid range
-                    if self.tasks.is_empty() {
-                        self.selected_task_index = 0;
-                    } else if self.selected_task_index >= self.tasks.len() {
-                        self.selected_task_index = self.tasks.len() - 1;
+            Action::Search if task_focus => {
+                self.input_mode = InputMode::SearchingTasks;
+            }
+
+            Action::CycleSort if task_focus => {
+                self.task_sort = self.task_sort.next();
+                self.config.task_sort = self.task_sort.name().to_string();
+                let _ = self.config.save();
+            }
+
+            Action::MoveTaskUp if task_focus && self.selected_task().is_some() => {
+                self.move_task(-1)
+            }
+            Action::MoveTaskDown if task_focus && self.selected_task().is_some() => {
+                self.move_task(1)
+            }
+
+            Action::AddTask if task_focus => {
+                self.input_mode = InputMode::AddingTask;
+                self.input_buffer.clear();
+            }
+
+            Action::DeleteTask if task_focus && self.selected_task().is_some() => {
+                self.checkpoint();
+                match self.selected_subtask {
+                    Some(index) => {
+                        let task = &mut self.tasks[self.selected_task_index];
+                        task.subtasks.remove(index);
+                        task.record(TaskEventKind::Edited);
+                        task.roll_up();
+                    }
+                    None => {
+                        let task = self.tasks.remove(self.selected_task_index);
+                        self.archive.add(task.to_data(), ArchiveReason::Deleted);
                     }
-                    self.needs_save = true;
                 }
+                self.clamp_selection();
+                self.needs_save = true;
             }
 
-            KeyCode::Char('e') | KeyCode::Char('E') => {
-                if (self.active_pane == ActivePane::Tasks || self.focus_mode) && !self.tasks.is_empty() {
-                    let task = &self.tasks[self.selected_task_index];
-                    // Pre-fill buffer: "Name #tag1 #tag2"
-                    self.input_buffer = task.name.clone();
-                    for tag in &task.tags {
-                        self.input_buffer.push_str(" #");
-                        self.input_buffer.push_str(tag);
+            Action::EditTask if task_focus && self.selected_task().is_some() => {
+                let task = &self.tasks[self.selected_task_index];
+                match self.selected_subtask {
+                    Some(index) => {
+                        self.input_buffer = task.subtasks[index].name.clone();
+                        self.input_mode = InputMode::EditingSubtask;
+                    }
+                    None => {
+                        // Pre-fill buffer: "Name #tag1 #tag2 !high ~4 @2026-10-20 -- notes"
+                        self.input_buffer = task.to_input().to_string();
+                        self.input_mode = InputMode::EditingTask;
                     }
-                    
-                    self.input_mode = InputMode::EditingTask;
                 }
             }
 
-            KeyCode::Char('c') | KeyCode::Char('C') => {
-                if self.active_pane == ActivePane::Tasks || self.focus_mode {
-                    // Clear all completed tasks
-                    self.tasks.retain(|t| !t.completed);
-                    // Clamp selected index
-                    if self.tasks.is_empty() {
-                        self.selected_task_index = 0;
-                    } else if self.selected_task_index >= self.tasks.len() {
-                        self.selected_task_index = self.tasks.len() - 1;
+            Action::ClearCompleted if task_focus => {
+                // Move the active list's completed tasks to the archive
+                let project = self.active_project.clone();
+                let cleared = |t: &Task| t.completed && t.project == project;
+                if self.tasks.iter().any(cleared) {
+                    self.checkpoint();
+                    let (done, open) = std::mem::take(&mut self.tasks)
+                        .into_iter()
+                        .partition(cleared);
+                    self.tasks = open;
+                    for task in done {
+                        self.archive.add(task.to_data(), ArchiveReason::Cleared);
                     }
+                    self.clamp_selection();
                     self.needs_save = true;
                 }
             }
 
-            KeyCode::Enter => {
-                if (self.active_pane == ActivePane::Tasks || self.focus_mode) && !self.tasks.is_empty() {
-                    self.tasks[self.selected_task_index].completed =
-                        !self.tasks[self.selected_task_index].completed;
-                    self.needs_save = true;
-                }
+            Action::Undo => self.undo(),
+            Action::Redo => self.redo(),
+
+            Action::ShowArchive => {
+                self.archive_cursor = 0;
+                self.input_mode = InputMode::BrowsingArchive;
+            }
+            Action::ManageTags => {
+                self.tag_cursor = 0;
+                self.input_mode = InputMode::ManagingTags;
             }
 
-            KeyCode::Esc => {
-                if self.focus_mode {
-                    self.focus_mode = false;
+            Action::ToggleDone if task_focus && self.selected_task().is_some() => {
+                self.checkpoint();
+                let task = &mut self.tasks[self.selected_task_index];
+                match self.selected_subtask {
+                    Some(index) => task.toggle_subtask(index),
+                    None => {
+                        let completed = !task.completed;
+                        task.set_completed(completed);
+                    }
                 }
+                // An is:open or is:done search may hide the task now
+                self.clamp_selection();
+                self.needs_save = true;
             }
 
-            _ => {}
+            Action::Back if self.focus_mode => {
+                self.focus_mode = false;
+            }
+            Action::Back if !self.task_query.is_empty() => self.set_task_query(String::new()),
+            Action::Back => {}
+
+            other => self.handle_global_action(other),
         }
     }
 
@@ -492,27 +1135,34 @@ impl App {
         match key {
             KeyCode::Enter => {
                 if !self.input_buffer.is_empty() {
-                    let (name, tags) = parse_task_input(&self.input_buffer);
+                    let input = parse_task_input(&self.input_buffer);
                     // Only create task if name is not empty (not just tags)
-                    if !name.trim().is_empty() {
+                    if !input.name.trim().is_empty() {
                         // Record tag usage
-                        if !tags.is_empty() {
-                            self.tag_store.record_usage(&tags);
+                        if !input.tags.is_empty() {
+                            self.tag_store.record_usage(&input.tags);
                         }
+                        self.checkpoint();
 
                         if self.input_mode == InputMode::EditingTask {
                             // Update existing task
-                             if let Some(task) = self.tasks.get_mut(self.selected_task_index) {
-                                task.name = name;
-                                task.tags = tags;
-                             }
+                            if let Some(task) = self.tasks.get_mut(self.selected_task_index) {
+                                let before = task.to_input();
+                                task.apply_input(input);
+                                if task.to_input() != before {
+                                    task.record(TaskEventKind::Edited);
+                                }
+                            }
+                            self.clamp_selection();
                         } else {
-                            // Create new task
-                            let task = Task::with_tags(name, tags);
+                            // Create new task, ending any search so it shows up
+                            let task = Task::from_input(input, &self.active_project);
+                            self.task_query.clear();
                             self.tasks.push(task);
                             self.selected_task_index = self.tasks.len() - 1;
+                            self.selected_subtask = None;
                         }
-                        
+
                         self.needs_save = true;
                     }
                 }
@@ -533,16 +1183,725 @@ impl App {
             }
             KeyCode::Backspace => {
                 self.input_buffer.pop();
-                self.update_tag_suggestion();
+                self.update_tag_suggestion();
+            }
+            KeyCode::Char(c) => {
+                self.input_buffer.push(c);
+                self.update_tag_suggestion();
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle the prompt for adding or renaming a subtask
+    fn handle_subtask_input_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Enter => {
+                let name = self.input_buffer.trim().to_string();
+                if !name.is_empty() && self.selected_task().is_some() {
+                    self.checkpoint();
+                    let task = &mut self.tasks[self.selected_task_index];
+                    match self
+                        .selected_subtask
+                        .filter(|_| self.input_mode == InputMode::EditingSubtask)
+                    {
+                        Some(index) => task.subtasks[index].name = name,
+                        None => {
+                            task.subtasks.push(Subtask::new(name));
+                            task.expanded = true;
+                            task.roll_up();
+                            self.selected_subtask = Some(task.subtasks.len() - 1);
+                        }
+                    }
+                    task.record(TaskEventKind::Edited);
+                    self.needs_save = true;
+                }
+                self.input_mode = InputMode::Normal;
+                self.input_buffer.clear();
+            }
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+                self.input_buffer.clear();
+            }
+            KeyCode::Backspace => {
+                self.input_buffer.pop();
+            }
+            KeyCode::Char(c) => {
+                self.input_buffer.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    /// Remember the task list before changing it, so the change can be undone
+    fn checkpoint(&mut self) {
+        self.undo_stack.push(self.task_list_snapshot());
+        if self.undo_stack.len() > UNDO_LIMIT {
+            self.undo_stack.remove(0);
+        }
+        self.redo_stack.clear();
+    }
+
+    fn task_list_snapshot(&self) -> TaskListSnapshot {
+        TaskListSnapshot {
+            tasks: self.tasks.clone(),
+            archive: self.archive.entries.clone(),
+            selected: (self.selected_task_index, self.selected_subtask),
+        }
+    }
+
+    fn restore_task_list(&mut self, snapshot: TaskListSnapshot) {
+        self.tasks = snapshot.tasks;
+        self.archive.entries = snapshot.archive;
+        (self.selected_task_index, self.selected_subtask) = snapshot.selected;
+        self.clamp_selection();
+        self.needs_save = true;
+    }
+
+    fn undo(&mut self) {
+        if let Some(snapshot) = self.undo_stack.pop() {
+            self.redo_stack.push(self.task_list_snapshot());
+            self.restore_task_list(snapshot);
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(snapshot) = self.redo_stack.pop() {
+            self.undo_stack.push(self.task_list_snapshot());
+            self.restore_task_list(snapshot);
+        }
+    }
+
+    /// Handle keys in the archive browser (newest first)
+    fn handle_archive_key(&mut self, key: KeyCode) {
+        let count = self.archive.entries.len();
+        // The browser lists the newest entry first
+        let index = count.checked_sub(self.archive_cursor + 1);
+        match key {
+            KeyCode::Char('j') | KeyCode::Down if count > 0 => {
+                self.archive_cursor = (self.archive_cursor + 1) % count;
+            }
+            KeyCode::Char('k') | KeyCode::Up if count > 0 => {
+                self.archive_cursor = (self.archive_cursor + count - 1) % count;
+            }
+            KeyCode::Enter | KeyCode::Char('r') => {
+                if let Some(index) = index {
+                    self.checkpoint();
+                    if let Some(data) = self.archive.take(index) {
+                        let mut task = Task::from(data);
+                        add_project(&mut self.projects, &task.project);
+                        task.record(TaskEventKind::Restored);
+                        self.tasks.push(task);
+                        if self
+                            .tasks
+                            .last()
+                            .is_some_and(|t| t.project == self.active_project)
+                        {
+                            (self.selected_task_index, self.selected_subtask) =
+                                (self.tasks.len() - 1, None);
+                        }
+                        self.needs_save = true;
+                    }
+                    self.archive_cursor = self
+                        .archive_cursor
+                        .min(self.archive.entries.len().saturating_sub(1));
+                }
+            }
+            KeyCode::Char('x') | KeyCode::Delete => {
+                // Forget the task for good (still undoable until the app closes)
+                if let Some(index) = index {
+                    self.checkpoint();
+                    self.archive.entries.remove(index);
+                    self.archive_cursor = self
+                        .archive_cursor
+                        .min(self.archive.entries.len().saturating_sub(1));
+                    self.needs_save = true;
+                }
+            }
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_tag_manager_key(&mut self, key: KeyCode) {
+        let names = self.tag_names();
+        let count = names.len();
+        let selected = names.get(self.tag_cursor).cloned();
+        match key {
+            KeyCode::Char('j') | KeyCode::Down if count > 0 => {
+                self.tag_cursor = (self.tag_cursor + 1) % count;
+            }
+            KeyCode::Char('k') | KeyCode::Up if count > 0 => {
+                self.tag_cursor = (self.tag_cursor + count - 1) % count;
+            }
+            KeyCode::Enter | KeyCode::Char('r') => {
+                if let Some(name) = selected {
+                    self.input_buffer = name;
+                    self.input_mode = InputMode::RenamingTag;
+                }
+            }
+            KeyCode::Char('c') => {
+                if let Some(name) = selected {
+                    self.tag_store.cycle_color(&name);
+                    let _ = self.tag_store.save();
+                }
+            }
+            KeyCode::Char('x') | KeyCode::Delete => {
+                if let Some(name) = selected {
+                    self.delete_tag(&name);
+                    self.tag_cursor = self
+                        .tag_cursor
+                        .min(self.tag_names().len().saturating_sub(1));
+                }
+            }
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle the prompt for a tag's new name; an existing name merges the two
+    fn handle_tag_rename_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Enter => {
+                let to = self
+                    .input_buffer
+                    .trim()
+                    .trim_start_matches('#')
+                    .trim_matches('/')
+                    .to_string();
+                let from = self.tag_names().get(self.tag_cursor).cloned();
+                if let Some(from) = from.filter(|from| *from != to) {
+                    if !to.is_empty() && !to.contains(char::is_whitespace) {
+                        self.rename_tag(&from, &to);
+                        self.tag_cursor = self
+                            .tag_names()
+                            .iter()
+                            .position(|name| name.eq_ignore_ascii_case(&to))
+                            .unwrap_or(0);
+                    }
+                }
+                self.input_buffer.clear();
+                self.input_mode = InputMode::ManagingTags;
+            }
+            KeyCode::Esc => {
+                self.input_buffer.clear();
+                self.input_mode = InputMode::ManagingTags;
+            }
+            KeyCode::Backspace => {
+                self.input_buffer.pop();
+            }
+            KeyCode::Char(c) => {
+                self.input_buffer.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    /// Every tag on a task or in the tag store, plus the parents of nested tags, as a sorted tree
+    pub fn tag_names(&self) -> Vec<String> {
+        let known = self
+            .tag_store
+            .tags
+            .iter()
+            .map(|t| t.name.as_str())
+            .chain(
+                self.tasks
+                    .iter()
+                    .flat_map(|t| t.tags.iter().map(String::as_str)),
+            )
+            .chain(
+                self.archive
+                    .entries
+                    .iter()
+                    .flat_map(|e| e.task.tags.iter().map(String::as_str)),
+            );
+
+        let mut names: Vec<String> = Vec::new();
+        for tag in known {
+            let mut current = Some(tag);
+            while let Some(name) = current {
+                if !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+                    names.push(name.to_string());
+                }
+                current = tags::parent(name);
+            }
+        }
+        names.sort_by_key(|name| {
+            name.to_lowercase()
+                .split('/')
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        });
+        names
+    }
+
+    /// Rename a tag (and the tags nested under it) on tasks, archived tasks, past sessions and
+    /// in the tag store; renaming onto an existing tag merges them
+    ///
+    /// Undo history is dropped: it only covers the task list, so undoing would bring the old
+    /// name back on tasks but not in sessions or the tag store.
+    fn rename_tag(&mut self, from: &str, to: &str) {
+        for task in &mut self.tasks {
+            tags::rename_in(&mut task.tags, from, to);
+        }
+        for entry in &mut self.archive.entries {
+            tags::rename_in(&mut entry.task.tags, from, to);
+        }
+        if self.session_history.rename_tag(from, to) > 0 {
+            let _ = self.session_history.save();
+        }
+        self.tag_store.rename(from, to);
+        let _ = self.tag_store.save();
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.needs_save = true;
+    }
+
+    /// Take a tag off every task and forget it; past sessions keep it
+    ///
+    /// Like a rename, this drops the undo history.
+    fn delete_tag(&mut self, name: &str) {
+        for task in &mut self.tasks {
+            tags::remove_from(&mut task.tags, name);
+        }
+        for entry in &mut self.archive.entries {
+            tags::remove_from(&mut entry.task.tags, name);
+        }
+        self.tag_store.remove(name);
+        let _ = self.tag_store.save();
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.needs_save = true;
+    }
+
+    /// Handle keys while typing a task search; the pane filters as you type
+    fn handle_search_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Enter => {
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Esc => {
+                self.set_task_query(String::new());
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Up if self.selected_task().is_some() => self.move_selection(-1),
+            KeyCode::Down if self.selected_task().is_some() => self.move_selection(1),
+            KeyCode::Backspace => {
+                let mut query = std::mem::take(&mut self.task_query);
+                query.pop();
+                self.set_task_query(query);
+            }
+            KeyCode::Char(c) => {
+                let query = format!("{}{}", self.task_query, c);
+                self.set_task_query(query);
+            }
+            _ => {}
+        }
+    }
+
+    /// Change the task search and select the best match (or keep the selection once it is cleared)
+    fn set_task_query(&mut self, query: String) {
+        self.task_query = query;
+        if self.task_query.is_empty() {
+            self.clamp_selection();
+        } else {
+            (self.selected_task_index, self.selected_subtask) =
+                self.task_rows().first().copied().unwrap_or((0, None));
+        }
+    }
+
+    /// Indexes into `tasks` of the tasks the pane shows, in display order
+    ///
+    /// That is the active list, narrowed by the search and ordered by the chosen
+    /// sort, or by how well names match while there is search text.
+    pub fn visible_tasks(&self) -> Vec<usize> {
+        let query = TaskQuery::parse(&self.task_query);
+        let matcher = SkimMatcherV2::default();
+        let mut visible: Vec<(usize, i64)> = self
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.project == self.active_project)
+            .filter_map(|(i, t)| Some((i, query.score(&matcher, &t.name, &t.tags, t.completed)?)))
+            .collect();
+
+        match self.task_sort {
+            TaskSort::Manual => {}
+            TaskSort::Pomodoros => {
+                visible.sort_by_key(|&(i, _)| std::cmp::Reverse(self.tasks[i].total_pomodoros()))
+            }
+            TaskSort::Created => {
+                visible.sort_by_key(|&(i, _)| std::cmp::Reverse(self.tasks[i].created_at))
+            }
+            TaskSort::Tag => visible.sort_by_key(|&(i, _)| match self.tasks[i].tags.first() {
+                Some(tag) => (false, tag.to_lowercase()),
+                None => (true, String::new()),
+            }),
+        }
+        if !query.text.is_empty() {
+            visible.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        }
+        visible.into_iter().map(|(i, _)| i).collect()
+    }
+
+    /// Whether the task pane is in the order tasks are stored, so they can be moved by hand
+    pub fn can_reorder(&self) -> bool {
+        self.task_sort == TaskSort::Manual && TaskQuery::parse(&self.task_query).text.is_empty()
+    }
+
+    /// Move the selected task past its visible neighbour, or a subtask within its task
+    fn move_task(&mut self, offset: isize) {
+        if !self.can_reorder() {
+            return;
+        }
+        let index = self.selected_task_index;
+        match self.selected_subtask {
+            Some(sub) => {
+                let count = self.tasks[index].subtasks.len();
+                let Some(other) = sub.checked_add_signed(offset).filter(|&s| s < count) else {
+                    return;
+                };
+                self.checkpoint();
+                self.tasks[index].subtasks.swap(sub, other);
+                self.selected_subtask = Some(other);
+            }
+            None => {
+                let visible = self.visible_tasks();
+                let Some(other) = visible
+                    .iter()
+                    .position(|&i| i == index)
+                    .and_then(|pos| pos.checked_add_signed(offset))
+                    .and_then(|pos| visible.get(pos).copied())
+                else {
+                    return;
+                };
+                self.checkpoint();
+                self.tasks.swap(index, other);
+                self.selected_task_index = other;
+            }
+        }
+        self.needs_save = true;
+    }
+
+    /// The selected task, if the task pane shows it
+    pub fn selected_task(&self) -> Option<&Task> {
+        self.tasks
+            .get(self.selected_task_index)
+            .filter(|_| self.visible_tasks().contains(&self.selected_task_index))
+    }
+
+    /// Rows of the task pane: each visible task, followed by its subtasks when expanded
+    pub fn task_rows(&self) -> Vec<(usize, Option<usize>)> {
+        let mut rows = Vec::new();
+        for i in self.visible_tasks() {
+            let task = &self.tasks[i];
+            rows.push((i, None));
+            if task.expanded {
+                rows.extend((0..task.subtasks.len()).map(|s| (i, Some(s))));
+            }
+        }
+        rows
+    }
+
+    /// Move the selection up or down a row, wrapping at either end
+    fn move_selection(&mut self, offset: isize) {
+        let rows = self.task_rows();
+        let current = rows
+            .iter()
+            .position(|&row| row == (self.selected_task_index, self.selected_subtask))
+            .unwrap_or(0);
+        let next = (current as isize + offset).rem_euclid(rows.len() as isize) as usize;
+        (self.selected_task_index, self.selected_subtask) = rows[next];
+    }
+
+    /// Keep the selection on a visible row after rows are removed or hidden
+    ///
+    /// Prefers the row that took the old one's place, then an earlier subtask of
+    /// the same task, then the next row, then the one before.
+    fn clamp_selection(&mut self) {
+        let rows = self.task_rows();
+        let wanted = (self.selected_task_index, self.selected_subtask);
+        if rows.contains(&wanted) {
+            return;
+        }
+        let same_task = rows
+            .iter()
+            .rev()
+            .find(|row| row.0 == wanted.0 && **row < wanted);
+        let after = rows.iter().find(|row| **row > wanted);
+        let before = rows.iter().rev().find(|row| **row < wanted);
+        (self.selected_task_index, self.selected_subtask) =
+            same_task.or(after).or(before).copied().unwrap_or((0, None));
+    }
+
+    /// The selected task and subtask, by id
+    fn selected_ids(&self) -> Option<(Uuid, Option<Uuid>)> {
+        let task = self.selected_task()?;
+        let subtask = self
+            .selected_subtask
+            .and_then(|i| task.subtasks.get(i))
+            .map(|s| s.id);
+        Some((task.id, subtask))
+    }
+
+    /// Credit a finished pomodoro to a task or one of its subtasks
+    ///
+    /// The credit goes into the undo and redo snapshots too, so undoing an earlier
+    /// edit doesn't take it back.
+    fn credit_pomodoro(&mut self, task_id: Uuid, subtask_id: Option<Uuid>) {
+        let Some(task) = self.tasks.iter_mut().find(|t| t.id == task_id) else {
+            return;
+        };
+        task.spend_pomodoro(subtask_id);
+        for snapshot in self.undo_stack.iter_mut().chain(&mut self.redo_stack) {
+            snapshot.credit_pomodoro(task_id, subtask_id);
+        }
+        self.needs_save = true;
+    }
+
+    /// Focus time spent so far in the current work phase
+    fn focused_time(&self) -> Duration {
+        if self.is_counting_up() {
+            self.elapsed_time
+        } else {
+            self.get_current_duration()
+                .saturating_sub(self.remaining_time)
+        }
+    }
+
+    /// Give the work time since the last call to the selected task or subtask
+    fn attribute_focus(&mut self) {
+        if self.timer_state != TimerState::Work {
+            return;
+        }
+        let focused = self.focused_time();
+        if focused < self.attributed {
+            // The phase started over without being recorded
+            self.focus_shares.clear();
+            self.attributed = Duration::ZERO;
+        }
+        let spent = focused - self.attributed;
+        self.attributed = focused;
+
+        let Some((task, subtask)) = self.selected_ids().filter(|_| !spent.is_zero()) else {
+            return;
+        };
+        match self
+            .focus_shares
+            .iter_mut()
+            .find(|(t, s, _)| *t == task && *s == subtask)
+        {
+            Some((_, _, time)) => *time += spent,
+            None => self.focus_shares.push((task, subtask, spent)),
+        }
+    }
+
+    /// Split a work session by where its time went, starting a fresh tally for the next one
+    ///
+    /// The task with the most time is credited. If nothing was tallied (the phase
+    /// began before a restart), it all goes to the selected task.
+    fn attribute_session(&mut self, session: Session, earns_pomodoro: bool) -> PendingSession {
+        self.attribute_focus();
+        self.attributed = Duration::ZERO;
+        let mut shares: Vec<TaskShare> = std::mem::take(&mut self.focus_shares)
+            .into_iter()
+            .filter_map(|(task_id, subtask_id, time)| {
+                let task = self.tasks.iter().find(|t| t.id == task_id)?;
+                Some(TaskShare {
+                    task_id,
+                    subtask_id,
+                    task_name: task.name.clone(),
+                    task_tags: task.tags.clone(),
+                    secs: time.as_secs(),
+                })
+            })
+            .collect();
+        sessions::settle_shares(&mut shares, session.duration_secs, MIN_SHARE_SECS);
+
+        if shares.is_empty() {
+            if let (Some(task), Some((task_id, subtask_id))) =
+                (self.selected_task(), self.selected_ids())
+            {
+                shares.push(TaskShare {
+                    task_id,
+                    subtask_id,
+                    task_name: task.name.clone(),
+                    task_tags: task.tags.clone(),
+                    secs: session.duration_secs,
+                });
+            }
+        }
+
+        PendingSession {
+            session,
+            credited: (!shares.is_empty()).then_some(0),
+            shares,
+            earns_pomodoro,
+        }
+    }
+
+    /// Record a work session, crediting its task with a pomodoro if it earned one
+    fn record_work_session(&mut self, pending: PendingSession) {
+        let earns_pomodoro = pending.earns_pomodoro;
+        let (session, credited) = pending.into_session();
+        if let (true, Some(share)) = (earns_pomodoro, credited) {
+            self.credit_pomodoro(share.task_id, share.subtask_id);
+        }
+        self.add_session(session);
+    }
+
+    /// Handle the custom duration prompt
+    fn handle_duration_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Enter => {
+                if let Some(secs) = parse_duration(&self.input_buffer) {
+                    self.set_timer_length(secs);
+                    self.input_mode = InputMode::Normal;
+                    self.input_buffer.clear();
+                }
+            }
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+                self.input_buffer.clear();
+            }
+            KeyCode::Tab => {
+                // Cycle through recently used durations
+                let recent = &self.config.recent_timer_secs;
+                if !recent.is_empty() {
+                    let next = parse_duration(&self.input_buffer)
+                        .and_then(|secs| recent.iter().position(|&s| s == secs))
+                        .map(|i| (i + 1) % recent.len())
+                        .unwrap_or(0);
+                    self.input_buffer = format_duration_input(recent[next]);
+                }
+            }
+            KeyCode::Backspace => {
+                self.input_buffer.pop();
+            }
+            KeyCode::Char(c) if self.input_buffer.len() < 12 => {
+                self.input_buffer.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle the profile picker
+    fn handle_profile_key(&mut self, key: KeyCode) {
+        let count = self.config.profiles.len();
+        match key {
+            KeyCode::Char('j') | KeyCode::Down if count > 0 => {
+                self.profile_cursor = (self.profile_cursor + 1) % count;
+            }
+            KeyCode::Char('k') | KeyCode::Up if count > 0 => {
+                self.profile_cursor = (self.profile_cursor + count - 1) % count;
+            }
+            KeyCode::Enter => {
+                if let Some(profile) = self.config.profiles.get(self.profile_cursor) {
+                    let name = profile.name.clone();
+                    self.switch_profile(&name);
+                }
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_project_key(&mut self, key: KeyCode) {
+        let count = self.projects.len();
+        match key {
+            KeyCode::Char('j') | KeyCode::Down if count > 0 => {
+                self.project_cursor = (self.project_cursor + 1) % count;
+            }
+            KeyCode::Char('k') | KeyCode::Up if count > 0 => {
+                self.project_cursor = (self.project_cursor + count - 1) % count;
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') => {
+                self.input_buffer.clear();
+                self.input_mode = InputMode::NamingProject;
+            }
+            KeyCode::Enter => {
+                if let Some(name) = self.projects.get(self.project_cursor).cloned() {
+                    self.switch_project(&name);
+                }
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle the prompt for a new task list's name
+    fn handle_project_name_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Enter => {
+                let name = self.input_buffer.trim().to_string();
+                if !name.is_empty() {
+                    self.switch_project(&name);
+                }
+                self.input_buffer.clear();
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Esc => {
+                self.input_buffer.clear();
+                self.input_mode = InputMode::PickingProject;
+            }
+            KeyCode::Backspace => {
+                self.input_buffer.pop();
             }
             KeyCode::Char(c) => {
                 self.input_buffer.push(c);
-                self.update_tag_suggestion();
             }
             _ => {}
         }
     }
 
+    /// Show another task list (creating it if needed) and select its first task
+    fn switch_project(&mut self, name: &str) {
+        add_project(&mut self.projects, name);
+        if name != self.active_project {
+            self.active_project = name.to_string();
+            self.task_query.clear();
+            (self.selected_task_index, self.selected_subtask) =
+                self.task_rows().first().copied().unwrap_or((0, None));
+        }
+        self.needs_save = true;
+    }
+
+    /// Make a profile active and restart the Pomodoro phase with its durations
+    fn switch_profile(&mut self, name: &str) {
+        if name == self.config.active_profile {
+            return;
+        }
+
+        if self.timer_mode == TimerMode::Pomodoro {
+            self.record_interrupted_session(Interruption::Reset, Utc::now());
+        }
+        if !self.config.use_profile(name) {
+            return;
+        }
+        self.sessions_before_long = self.config.sessions_before_long_break;
+        let _ = self.config.save();
+
+        if self.timer_mode == TimerMode::Pomodoro {
+            self.session_count = self
+                .session_count
+                .min(self.sessions_before_long.saturating_sub(1));
+            self.remaining_time = self.duration_for_state(self.timer_state);
+            self.start_remaining = self.remaining_time;
+            self.start_instant = None;
+            self.is_paused = true;
+        }
+    }
+
     /// Update tag suggestion based on current input
     fn update_tag_suggestion(&mut self) {
         // Find the last incomplete tag in input
@@ -615,13 +1974,13 @@ impl App {
                 // How to submit then? Enter.
                 // But the requirement says "Spacebar to skip/continue".
                 // I'll implement: Empty Buffer + Space = Skip & Start. Non-empty + Space = Type space.
-                
+
                 if self.input_buffer.is_empty() {
                     self.complete_pending_session(None);
                     self.input_mode = InputMode::Normal;
                     self.input_buffer.clear();
                     // Auto-start next state
-                    self.toggle_pause(); 
+                    self.toggle_pause();
                 } else {
                      if self.input_buffer.len() < 60 {
                         self.input_buffer.push(' ');
@@ -635,13 +1994,17 @@ impl App {
                 self.input_buffer.clear();
                 self.is_paused = true;
             }
+            // Tab -> Credit the session to another task it was split with
+            KeyCode::Tab => {
+                if let Some(pending) = &mut self.pending_session {
+                    pending.reassign();
+                }
+            }
             KeyCode::Backspace => {
                 self.input_buffer.pop();
             }
-            KeyCode::Char(c) => {
-                if self.input_buffer.len() < 60 {
-                    self.input_buffer.push(c);
-                }
+            KeyCode::Char(c) if self.input_buffer.len() < 60 => {
+                self.input_buffer.push(c);
             }
             _ => {}
         }
@@ -673,88 +2036,223 @@ impl App {
         Local::now().hour() >= 23
     }
 
-    fn handle_dashboard_key(&mut self, key: KeyCode) {
+    fn handle_dashboard_key(&mut self, key: KeyEvent) {
         // Quick capture check is done in handle_key
+        if self.input_mode == InputMode::Exporting {
+            self.handle_export_key(key.code);
+            return;
+        }
+
+        match self.keymap.action(KeyContext::Dashboard, key) {
+            Some(Action::PickProfile) => self.cycle_profile_filter(),
+            Some(Action::Export) => {
+                // Start from the dashboard's profile filter
+                self.input_buffer = self
+                    .profile_filter
+                    .as_ref()
+                    .map(|p| format!("profile:{} ", p))
+                    .unwrap_or_default();
+                self.export_result = None;
+                self.input_mode = InputMode::Exporting;
+            }
+            Some(action) => self.handle_global_action(action),
+            None => {}
+        }
+    }
+
+    /// Handle the export prompt: a filter query, Tab for the format, Enter to write
+    fn handle_export_key(&mut self, key: KeyCode) {
+        // Any key dismisses the result of the last export
+        if self.export_result.is_some() {
+            self.export_result = None;
+            self.input_mode = InputMode::Normal;
+            self.input_buffer.clear();
+            return;
+        }
 
         match key {
-            KeyCode::Char('q') | KeyCode::Char('Q') => {
-                self.save_all();
-                self.should_quit = true;
+            KeyCode::Enter => {
+                self.export_result = Some(self.export_sessions().map_err(|e| e.to_string()));
+            }
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+                self.input_buffer.clear();
+            }
+            KeyCode::Tab => {
+                self.export_format = self.export_format.next();
+            }
+            KeyCode::Backspace => {
+                self.input_buffer.pop();
+            }
+            KeyCode::Char(c) if self.input_buffer.len() < 60 => {
+                self.input_buffer.push(c);
             }
-            KeyCode::Char('1') => self.current_view = CurrentView::Timer,
-            KeyCode::Char('2') => self.current_view = CurrentView::Dashboard,
-            KeyCode::Char('3') => self.current_view = CurrentView::Settings,
-            KeyCode::Esc => self.current_view = CurrentView::Timer,
             _ => {}
         }
     }
 
-    fn handle_settings_key(&mut self, key: KeyCode) {
+    /// Export the sessions matching the prompt's query, returning a summary
+    fn export_sessions(&self) -> anyhow::Result<String> {
+        let mut filter = ExportFilter::parse(&self.input_buffer)?;
+        filter.day = DayBoundary::from_config(&self.config);
+        let sessions = filter.apply(self.session_history.sessions());
+        let path = export::default_path(self.export_format)?;
+        let count = export::export_to_file(self.export_format, &sessions, &path)?;
+        Ok(format!("{} sessions → {}", count, path.display()))
+    }
+
+    /// Step the dashboard filter through all profiles and back to everything
+    fn cycle_profile_filter(&mut self) {
+        let names: Vec<&String> = self.config.profiles.iter().map(|p| &p.name).collect();
+        let next = match &self.profile_filter {
+            None => names.first(),
+            Some(current) => names
+                .iter()
+                .position(|n| *n == current)
+                .and_then(|i| names.get(i + 1)),
+        };
+        self.profile_filter = next.map(|n| n.to_string());
+    }
+
+    /// Session history as the dashboard shows it (filtered by profile if one is picked)
+    pub fn dashboard_history(&self) -> Cow<'_, SessionHistory> {
+        match &self.profile_filter {
+            Some(profile) => Cow::Owned(self.session_history.for_profile(profile)),
+            None => Cow::Borrowed(&self.session_history),
+        }
+    }
+
+    /// Cycle and wait times over the task list
+    pub fn task_flow(&self) -> TaskFlow {
+        TaskFlow::new(self.tasks.iter().map(|task| task.lifecycle()), Utc::now())
+    }
+
+    fn handle_settings_key(&mut self, key: KeyEvent) {
         // Handle confirm reset mode
         if self.input_mode == InputMode::ConfirmReset {
-            self.handle_confirm_reset_key(key);
+            self.handle_confirm_reset_key(key.code);
             return;
         }
 
-        match key {
-            KeyCode::Char('q') | KeyCode::Char('Q') => {
-                self.save_all();
-                self.should_quit = true;
-            }
-            KeyCode::Char('1') => self.current_view = CurrentView::Timer,
-            KeyCode::Char('2') => self.current_view = CurrentView::Dashboard,
-            KeyCode::Char('3') => self.current_view = CurrentView::Settings,
-            KeyCode::Esc => self.current_view = CurrentView::Timer,
-
-            KeyCode::Char('j') | KeyCode::Down => {
+        match self.keymap.action(KeyContext::Settings, key) {
+            Some(Action::Down) => {
                 self.selected_setting = self.selected_setting.next();
             }
-            KeyCode::Char('k') | KeyCode::Up => {
+            Some(Action::Up) => {
                 self.selected_setting = self.selected_setting.prev();
             }
 
-            KeyCode::Char('l') | KeyCode::Right | KeyCode::Enter => {
-                self.adjust_setting(1);
-            }
-            KeyCode::Char('h') | KeyCode::Left => {
-                self.adjust_setting(-1);
-            }
+            Some(Action::Increase) => self.adjust_setting(1),
+            Some(Action::Decrease) => self.adjust_setting(-1),
 
-            _ => {}
+            Some(other) => self.handle_global_action(other),
+            None => {}
         }
     }
 
     fn adjust_setting(&mut self, delta: i64) {
         match self.selected_setting {
+            SettingsField::Profile => {
+                let count = self.config.profiles.len() as i64;
+                if count > 0 {
+                    let idx = self
+                        .config
+                        .profiles
+                        .iter()
+                        .position(|p| p.name == self.config.active_profile)
+                        .unwrap_or(0) as i64;
+                    let next = (idx + delta).rem_euclid(count) as usize;
+                    let name = self.config.profiles[next].name.clone();
+                    self.switch_profile(&name);
+                }
+            }
             SettingsField::WorkDuration => {
-                let new_val = (self.config.work_duration_mins as i64 + delta).clamp(1, 120);
+                let new_val = stepped(
+                    self.config.work_duration_mins as i64,
+                    delta,
+                    config::WORK_MINS,
+                );
                 self.config.work_duration_mins = new_val as u64;
             }
             SettingsField::ShortBreak => {
-                let new_val = (self.config.short_break_mins as i64 + delta).clamp(1, 60);
+                let new_val = stepped(
+                    self.config.short_break_mins as i64,
+                    delta,
+                    config::BREAK_MINS,
+                );
                 self.config.short_break_mins = new_val as u64;
             }
             SettingsField::LongBreak => {
-                let new_val = (self.config.long_break_mins as i64 + delta).clamp(1, 60);
+                let new_val = stepped(
+                    self.config.long_break_mins as i64,
+                    delta,
+                    config::BREAK_MINS,
+                );
                 self.config.long_break_mins = new_val as u64;
             }
             SettingsField::SessionsBeforeLong => {
-                let new_val = (self.config.sessions_before_long_break as i64 + delta).clamp(1, 10);
+                let new_val = stepped(
+                    self.config.sessions_before_long_break as i64,
+                    delta,
+                    config::SESSIONS_BEFORE_LONG,
+                );
                 self.config.sessions_before_long_break = new_val as u8;
                 self.sessions_before_long = new_val as u8;
             }
             SettingsField::DailyGoal => {
-                let new_val = (self.config.daily_goal_pomodoros as i64 + delta).clamp(1, 20);
+                let new_val = stepped(
+                    self.config.daily_goal_pomodoros as i64,
+                    delta,
+                    config::DAILY_GOAL,
+                );
                 self.config.daily_goal_pomodoros = new_val as u8;
+                self.session_history.use_config(&self.config);
             }
             SettingsField::ShowStreak => {
                 self.config.show_streak = !self.config.show_streak;
             }
+            SettingsField::StreakRule => {
+                // 1..=10 pomodoros a day, then "reach the daily goal"
+                let current = if self.config.streak_needs_goal {
+                    10
+                } else {
+                    (self.config.streak_min_pomodoros.clamp(1, 10) - 1) as i64
+                };
+                let next = (current + delta).rem_euclid(11);
+                self.config.streak_needs_goal = next == 10;
+                if next < 10 {
+                    self.config.streak_min_pomodoros = next as u8 + 1;
+                }
+                self.session_history.use_config(&self.config);
+            }
+            SettingsField::DayStartHour => {
+                self.config.day_start_hour =
+                    (self.config.day_start_hour as i64 + delta).rem_euclid(24) as u8;
+                self.session_history.use_config(&self.config);
+            }
+            SettingsField::Theme => {
+                // Cycle through built-in and user themes, applying immediately
+                let names = Theme::available();
+                let idx = names
+                    .iter()
+                    .position(|n| *n == self.config.theme)
+                    .unwrap_or(0) as i64;
+                let next = (idx + delta).rem_euclid(names.len() as i64) as usize;
+                self.config.theme = names[next].clone();
+                self.theme = Theme::load(&self.config.theme).unwrap_or_else(|e| {
+                    self.storage_errors.push(format!("{:#}", e));
+                    Theme::default()
+                });
+            }
             SettingsField::BreathingAnimation => {
                 self.config.breathing_enabled = !self.config.breathing_enabled;
             }
             SettingsField::HideHintsAfter => {
-                let new_val = (self.config.hide_hints_after_secs as i64 + delta).clamp(0, 10);
+                let new_val = stepped(
+                    self.config.hide_hints_after_secs as i64,
+                    delta,
+                    config::HIDE_HINTS_SECS,
+                );
                 self.config.hide_hints_after_secs = new_val as u8;
             }
             SettingsField::AutoStartBreaks => {
@@ -763,9 +2261,30 @@ impl App {
             SettingsField::FocusModeOnStart => {
                 self.config.focus_mode_on_start = !self.config.focus_mode_on_start;
             }
+            SettingsField::TagRetention => {
+                let idx = TAG_RETENTION_STEPS
+                    .iter()
+                    .position(|&d| d >= self.config.tag_retention_days)
+                    .unwrap_or(TAG_RETENTION_STEPS.len() - 1) as i64;
+                let next = (idx + delta).rem_euclid(TAG_RETENTION_STEPS.len() as i64) as usize;
+                self.config.tag_retention_days = TAG_RETENTION_STEPS[next];
+            }
             SettingsField::NotificationsEnabled => {
                 self.config.notifications_enabled = !self.config.notifications_enabled;
             }
+            SettingsField::SoundEnabled => {
+                self.config.sound_enabled = !self.config.sound_enabled;
+                self.preview_sound();
+            }
+            SettingsField::SoundVolume => {
+                let new_val = stepped(
+                    self.config.sound_volume as i64,
+                    delta * 10,
+                    config::SOUND_VOLUME,
+                );
+                self.config.sound_volume = new_val as u8;
+                self.preview_sound();
+            }
             SettingsField::ResetData => {
                 // Start confirmation flow
                 self.input_mode = InputMode::ConfirmReset;
@@ -773,6 +2292,10 @@ impl App {
                 return; // Don't save config
             }
         }
+        // Duration edits belong to the active profile
+        if self.selected_setting.category() == SettingsCategory::Timer {
+            self.config.sync_active_profile();
+        }
         let _ = self.config.save();
     }
 
@@ -793,10 +2316,8 @@ impl App {
             KeyCode::Backspace => {
                 self.input_buffer.pop();
             }
-            KeyCode::Char(c) => {
-                if self.input_buffer.len() < 10 {
-                    self.input_buffer.push(c.to_ascii_uppercase());
-                }
+            KeyCode::Char(c) if self.input_buffer.len() < 10 => {
+                self.input_buffer.push(c.to_ascii_uppercase());
             }
             _ => {}
         }
@@ -805,12 +2326,16 @@ impl App {
     /// Reset all user data to defaults
     fn reset_all_data(&mut self) {
         // Clear sessions
-        self.session_history = SessionHistory::default();
+        self.session_history.clear();
         let _ = self.session_history.save();
 
         // Clear tasks
         self.tasks.clear();
         self.selected_task_index = 0;
+        self.selected_subtask = None;
+        self.archive = ArchiveStore::default();
+        self.undo_stack.clear();
+        self.redo_stack.clear();
         self.needs_save = true;
 
         // Clear tags
@@ -827,8 +2352,9 @@ impl App {
         if self.is_paused {
             self.start_instant = Some(Instant::now());
             self.start_remaining = self.remaining_time;
+            self.start_elapsed = self.elapsed_time;
             self.is_paused = false;
-            
+
             // Auto-enter focus mode if configured
             if self.config.focus_mode_on_start {
                 self.focus_mode = true;
@@ -841,56 +2367,225 @@ impl App {
     }
 
     fn reset_timer(&mut self) {
+        self.record_interrupted_session(Interruption::Reset, Utc::now());
         self.remaining_time = self.get_current_duration();
         self.start_remaining = self.remaining_time;
+        self.elapsed_time = Duration::ZERO;
         self.start_instant = None;
         self.is_paused = true;
     }
 
     fn skip_to_next(&mut self) {
-        if self.timer_mode == TimerMode::Pomodoro {
-            self.advance_pomodoro_state();
+        match self.timer_mode {
+            TimerMode::Pomodoro => {
+                self.record_interrupted_session(Interruption::Skipped, Utc::now());
+                self.advance_pomodoro_state();
+            }
+            TimerMode::Stopwatch if self.timer_state == TimerState::Work => self.stop_stopwatch(),
+            TimerMode::Stopwatch => self.restart_stopwatch(),
+            TimerMode::Timer(_) => {}
+        }
+    }
+
+    /// End a flow session: record the real time spent and offer a proportional break
+    fn stop_stopwatch(&mut self) {
+        self.update_remaining_time();
+        let elapsed = self.elapsed_time.as_secs();
+        if elapsed < MIN_INTERRUPTED_SECS {
+            self.restart_stopwatch();
+            return;
         }
+
+        self.pending_session =
+            Some(self.attribute_session(Session::new(SessionType::Work, elapsed, None), false));
+        self.input_mode = InputMode::SessionNote;
+        self.input_buffer.clear();
+
+        // Flowtime: break for a fraction of the focus time, never under a minute
+        let break_secs = (elapsed / self.config.flow_break_divisor.max(1)).max(60);
+        self.flow_break = Duration::from_secs(break_secs);
+        self.timer_state = TimerState::ShortBreak;
+        self.remaining_time = self.flow_break;
+        self.start_remaining = self.remaining_time;
+        self.elapsed_time = Duration::ZERO;
+        self.start_instant = None;
+        self.is_paused = true;
+    }
+
+    /// Put the stopwatch back to 00:00, ready for the next flow session
+    fn restart_stopwatch(&mut self) {
+        self.timer_state = TimerState::Work;
+        self.remaining_time = Duration::ZERO;
+        self.start_remaining = Duration::ZERO;
+        self.elapsed_time = Duration::ZERO;
+        self.start_instant = None;
+        self.is_paused = true;
+    }
+
+    /// Whether the clock is counting up rather than down
+    pub fn is_counting_up(&self) -> bool {
+        self.timer_mode == TimerMode::Stopwatch && self.timer_state == TimerState::Work
     }
 
     fn toggle_mode(&mut self) {
+        self.record_interrupted_session(Interruption::Reset, Utc::now());
         match self.timer_mode {
             TimerMode::Pomodoro => {
-                self.timer_mode = TimerMode::Timer(self.config.work_duration_mins * 60);
-                self.remaining_time = Duration::from_secs(self.config.work_duration_mins * 60);
+                // Pick up the last custom length, if there is one
+                let secs = self
+                    .config
+                    .recent_timer_secs
+                    .first()
+                    .copied()
+                    .unwrap_or(self.config.work_duration_mins * 60);
+                self.timer_mode = TimerMode::Timer(secs);
+                self.remaining_time = Duration::from_secs(secs);
             }
             TimerMode::Timer(_) => {
+                self.timer_mode = TimerMode::Stopwatch;
+                self.timer_state = TimerState::Work;
+                self.remaining_time = Duration::ZERO;
+            }
+            TimerMode::Stopwatch => {
                 self.timer_mode = TimerMode::Pomodoro;
                 self.timer_state = TimerState::Work;
                 self.remaining_time = self.duration_for_state(TimerState::Work);
                 self.session_count = 0;
             }
         }
+        self.elapsed_time = Duration::ZERO;
+        self.start_remaining = self.remaining_time;
+        self.start_instant = None;
+        self.is_paused = true;
+    }
+
+    /// Switch to Timer mode with a fresh countdown of the given length
+    fn set_timer_length(&mut self, secs: u64) {
+        self.record_interrupted_session(Interruption::Reset, Utc::now());
+        if self.timer_mode == TimerMode::Pomodoro {
+            self.session_count = 0;
+        }
+
+        self.timer_mode = TimerMode::Timer(secs);
+        self.timer_state = TimerState::Work;
+        self.elapsed_time = Duration::ZERO;
+        self.remaining_time = Duration::from_secs(secs);
         self.start_remaining = self.remaining_time;
         self.start_instant = None;
         self.is_paused = true;
+
+        self.remember_timer_secs(secs);
+    }
+
+    /// Put a Timer-mode length at the front of the recent list and save it right away
+    fn remember_timer_secs(&mut self, secs: u64) {
+        self.config.remember_timer_secs(secs);
+        if let Err(error) = self.config.save() {
+            self.storage_errors.push(format!("{:#}", error));
+        }
+    }
+
+    /// Lengthen or shorten the Timer-mode countdown, keeping time already spent
+    fn adjust_timer_length(&mut self, delta: i64) {
+        let TimerMode::Timer(secs) = self.timer_mode else {
+            return;
+        };
+        let new_secs =
+            (secs as i64 + delta).clamp(MIN_TIMER_SECS as i64, MAX_TIMER_SECS as i64) as u64;
+        if new_secs == secs {
+            return;
+        }
+
+        self.update_remaining_time();
+        self.remaining_time = if new_secs > secs {
+            self.remaining_time + Duration::from_secs(new_secs - secs)
+        } else {
+            self.remaining_time
+                .saturating_sub(Duration::from_secs(secs - new_secs))
+                .max(Duration::from_secs(1))
+        };
+        self.timer_mode = TimerMode::Timer(new_secs);
+        self.start_remaining = self.remaining_time;
+        if self.start_instant.is_some() {
+            self.start_instant = Some(Instant::now());
+        }
+    }
+
+    /// Credit a new session to the selected task, if there is one
+    fn task_session(&self, mut session: Session) -> Session {
+        if let Some(task) = self.selected_task() {
+            session.link_task(task.id, &task.name, &task.tags);
+        }
+        session
+    }
+
+    /// Tag a session with the active profile and add it to the history
+    fn add_session(&mut self, mut session: Session) {
+        session.profile = Some(self.config.active_profile.clone());
+        session.project = Some(self.active_project.clone());
+        session.mode = match self.timer_mode {
+            TimerMode::Pomodoro => SessionMode::Pomodoro,
+            TimerMode::Timer(_) => SessionMode::Timer,
+            TimerMode::Stopwatch => SessionMode::Stopwatch,
+        };
+        self.session_history.add(session);
+    }
+
+    /// Record the current work phase as cut short, if any real focus went into it
+    fn record_interrupted_session(&mut self, reason: Interruption, ended_at: DateTime<Utc>) {
+        if self.timer_state != TimerState::Work {
+            return;
+        }
+
+        self.update_remaining_time();
+        let elapsed = self.focused_time().as_secs();
+        if elapsed < MIN_INTERRUPTED_SECS {
+            return;
+        }
+
+        let session = Session::interrupted(self.timer_state.session_type(), elapsed, None, reason);
+        let mut pending = self.attribute_session(session, false);
+        pending.session.timestamp = ended_at;
+        self.record_work_session(pending);
+        let _ = self.session_history.save();
     }
 
     fn get_current_duration(&self) -> Duration {
         match self.timer_mode {
             TimerMode::Pomodoro => self.duration_for_state(self.timer_state),
             TimerMode::Timer(secs) => Duration::from_secs(secs),
+            // A stopwatch has no set length; its break does
+            TimerMode::Stopwatch if self.timer_state == TimerState::Work => Duration::ZERO,
+            TimerMode::Stopwatch => self.flow_break,
         }
     }
 
     fn update_remaining_time(&mut self) {
         if let Some(start) = self.start_instant {
             let elapsed = start.elapsed();
-            self.remaining_time = self.start_remaining.saturating_sub(elapsed);
+            if self.is_counting_up() {
+                self.elapsed_time = self.start_elapsed + elapsed;
+            } else {
+                self.remaining_time = self.start_remaining.saturating_sub(elapsed);
+            }
         }
     }
 
     fn advance_pomodoro_state(&mut self) {
-        if self.timer_state == TimerState::Work && !self.tasks.is_empty() {
-            self.tasks[self.selected_task_index].pomodoros_spent += 1;
-            self.needs_save = true;
+        self.next_pomodoro_state();
+
+        self.remaining_time = self.duration_for_state(self.timer_state);
+        self.start_remaining = self.remaining_time;
+        self.start_instant = None;
+        self.is_paused = !self.config.auto_start_breaks || self.timer_state == TimerState::Work;
+
+        if !self.is_paused {
+            self.start_instant = Some(Instant::now());
         }
+    }
 
+    /// Move to the next phase of the Pomodoro cycle
+    fn next_pomodoro_state(&mut self) {
         match self.timer_state {
             TimerState::Work => {
                 self.session_count += 1;
@@ -905,15 +2600,6 @@ impl App {
                 self.timer_state = TimerState::Work;
             }
         }
-
-        self.remaining_time = self.duration_for_state(self.timer_state);
-        self.start_remaining = self.remaining_time;
-        self.start_instant = None;
-        self.is_paused = !self.config.auto_start_breaks || self.timer_state == TimerState::Work;
-
-        if !self.is_paused {
-            self.start_instant = Some(Instant::now());
-        }
     }
 
     pub fn tick(&mut self) {
@@ -941,8 +2627,9 @@ impl App {
 
         if !self.is_paused {
             self.update_remaining_time();
+            self.attribute_focus();
 
-            if self.remaining_time.is_zero() {
+            if self.remaining_time.is_zero() && !self.is_counting_up() {
                 self.on_timer_complete();
             }
         }
@@ -951,57 +2638,65 @@ impl App {
             self.save_tasks();
             self.needs_save = false;
         }
+
+        // Snapshot the timer every ~5 seconds
+        self.snapshot_counter += 1;
+        if self.snapshot_counter >= 50 {
+            self.snapshot_counter = 0;
+            self.save_timer_state();
+        }
     }
 
     fn on_timer_complete(&mut self) {
-        let task_name = if !self.tasks.is_empty() {
-            Some(self.tasks[self.selected_task_index].name.clone())
-        } else {
-            None
-        };
-
-        // For work sessions, prompt for a note before saving
-        if self.timer_state == TimerState::Work {
-            // Increment pomodoro count for selected task
-            if !self.tasks.is_empty() {
-                self.tasks[self.selected_task_index].pomodoros_spent += 1;
-                self.needs_save = true;
-            }
-
-            self.pending_session = Some((
-                self.timer_state.session_type().to_string(),
-                self.get_current_duration().as_secs(),
-                task_name.clone(),
-            ));
+        let session = Session::new(
+            self.timer_state.session_type(),
+            self.get_current_duration().as_secs(),
+            None,
+        );
+
+        // For work sessions, prompt for a note (and who gets the pomodoro) before saving.
+        // A custom-length timer records its focus time but earns no pomodoro.
+        let task_name = if self.timer_state == TimerState::Work {
+            let earns_pomodoro = self.timer_mode == TimerMode::Pomodoro;
+            let pending = self.attribute_session(session, earns_pomodoro);
+            let task_name = pending
+                .credited_share()
+                .map(|share| share.task_name.clone());
+            self.pending_session = Some(pending);
             self.input_mode = InputMode::SessionNote;
             self.input_buffer.clear();
-            
+
             // Check for celebration triggers before showing note prompt
-            self.check_celebrations();
+            if earns_pomodoro {
+                self.check_celebrations();
+            }
+            task_name
         } else {
             // Breaks don't need notes
-            let session = Session::new(
-                self.timer_state.session_type(),
-                self.get_current_duration().as_secs(),
-                task_name.clone(),
-            );
-            self.session_history.add(session);
+            let session = self.task_session(session);
+            let task_name = session.task_name.clone();
+            self.add_session(session);
             let _ = self.session_history.save();
-        }
+            task_name
+        };
 
         self.send_notification(&task_name);
+        self.play_sound();
 
-        if self.timer_mode == TimerMode::Pomodoro {
-            self.advance_pomodoro_state();
-        } else {
-            self.is_paused = true;
+        match self.timer_mode {
+            TimerMode::Pomodoro => self.advance_pomodoro_state(),
+            TimerMode::Timer(secs) => {
+                self.remember_timer_secs(secs);
+                self.is_paused = true;
+            }
+            TimerMode::Stopwatch => self.restart_stopwatch(),
         }
     }
 
     /// Check and trigger celebration messages
     fn check_celebrations(&mut self) {
         let (completed, goal) = self.daily_goal_progress();
-        
+
         // Daily goal reached exactly
         if completed + 1 == goal as usize {
             self.show_celebration = true;
@@ -1011,7 +2706,7 @@ impl App {
         }
 
         // Streak milestones
-        let streak = self.session_history.current_streak;
+        let streak = self.session_history.current_streak();
         if streak == 7 {
             self.show_celebration = true;
             self.celebration_message = "🔥 Amazing! 7-day streak!".to_string();
@@ -1028,11 +2723,11 @@ impl App {
 
         // Hourly milestone
         let today_mins = self.session_history.today_focus_secs() / 60;
-        if today_mins >= 60 && today_mins < 85 {
+        if (60..85).contains(&today_mins) {
             self.show_celebration = true;
             self.celebration_message = "💪 1 hour of focus today!".to_string();
             self.celebration_timer = 40;
-        } else if today_mins >= 120 && today_mins < 145 {
+        } else if (120..145).contains(&today_mins) {
             self.show_celebration = true;
             self.celebration_message = "🚀 2 hours of focus today!".to_string();
             self.celebration_timer = 40;
@@ -1041,34 +2736,30 @@ impl App {
 
     /// Complete pending session with note
     fn complete_pending_session(&mut self, note: Option<String>) {
-        if let Some((session_type, duration, task_name)) = self.pending_session.take() {
-            let session = Session::with_note(&session_type, duration, task_name, note);
-            self.session_history.add(session);
+        if let Some(mut pending) = self.pending_session.take() {
+            pending.session.note = note;
+            self.record_work_session(pending);
             let _ = self.session_history.save();
         }
     }
 
     fn save_tasks(&self) {
-        use crate::persistence::tasks::{TaskData, TaskStore};
-
-        let tasks: Vec<TaskData> = self
-            .tasks
-            .iter()
-            .map(|t| TaskData {
-                id: t.id,
-                name: t.name.clone(),
-                completed: t.completed,
-                pomodoros_spent: t.pomodoros_spent,
-                tags: t.tags.clone(),
-                created_at: chrono::Utc::now(),
-            })
-            .collect();
-
-        let store = TaskStore { tasks };
+        let _ = self.archive.save();
+        let store = TaskStore {
+            tasks: self.tasks.iter().map(Task::to_data).collect(),
+            projects: self.projects.clone(),
+            active_project: self.active_project.clone(),
+        };
         let _ = store.save();
     }
 
-    fn save_all(&self) {
+    fn save_all(&mut self) {
+        // A finished work session still waiting for its note is recorded without one
+        if self.pending_session.is_some() {
+            self.complete_pending_session(None);
+        }
+
+        self.save_timer_state();
         self.save_tasks();
         let _ = self.config.save();
         let _ = self.session_history.save();
@@ -1101,14 +2792,41 @@ impl App {
     #[cfg(not(feature = "notifications"))]
     fn send_notification(&self, _task_name: &Option<String>) {}
 
+    #[cfg(feature = "audio")]
+    fn play_sound(&mut self) {
+        self.sound_player.play_for(self.timer_state, &self.config);
+    }
+
+    #[cfg(not(feature = "audio"))]
+    fn play_sound(&mut self) {}
+
+    /// Play the work chime so volume changes can be heard immediately
+    #[cfg(feature = "audio")]
+    fn preview_sound(&mut self) {
+        self.sound_player.play_for(TimerState::Work, &self.config);
+    }
+
+    #[cfg(not(feature = "audio"))]
+    fn preview_sound(&mut self) {}
+
     pub fn formatted_time(&self) -> String {
-        let total_secs = self.remaining_time.as_secs();
+        let total_secs = if self.is_counting_up() {
+            self.elapsed_time.as_secs()
+        } else {
+            self.remaining_time.as_secs()
+        };
         let mins = total_secs / 60;
         let secs = total_secs % 60;
         format!("{:02}:{:02}", mins, secs)
     }
 
     pub fn progress(&self) -> f64 {
+        // Measure a flow session against one pomodoro
+        if self.is_counting_up() {
+            let pomodoro = self.duration_for_state(TimerState::Work).as_secs_f64();
+            return (self.elapsed_time.as_secs_f64() / pomodoro).min(1.0);
+        }
+
         let total = self.get_current_duration().as_secs_f64();
         let remaining = self.remaining_time.as_secs_f64();
         if total > 0.0 {
@@ -1118,12 +2836,18 @@ impl App {
         }
     }
 
-
-
     pub fn mode_display(&self) -> String {
         match self.timer_mode {
-            TimerMode::Pomodoro => format!("● Pomodoro: {}", self.timer_state.display_name()),
-            TimerMode::Timer(_) => "○ Timer Mode".to_string(),
+            TimerMode::Pomodoro => format!(
+                "● {}: {}",
+                self.config.active_profile,
+                self.timer_state.display_name()
+            ),
+            TimerMode::Timer(secs) => format!("○ Timer Mode: {}", format_duration_input(secs)),
+            TimerMode::Stopwatch if self.timer_state == TimerState::Work => {
+                "◎ Stopwatch: Flow".to_string()
+            }
+            TimerMode::Stopwatch => "◎ Stopwatch: Break".to_string(),
         }
     }
 
@@ -1142,8 +2866,220 @@ impl App {
     }
 }
 
-impl Default for App {
-    fn default() -> Self {
-        Self::new()
+/// A settings value moved by `delta` and kept within `range`
+fn stepped(value: i64, delta: i64, range: RangeInclusive<i64>) -> i64 {
+    (value + delta).clamp(*range.start(), *range.end())
+}
+
+/// Parse a Timer-mode length such as "45" (minutes), "1h30m", "90s" or "1h 5m"
+/// Returns seconds, or None if the text is malformed or outside 1 min – 3 h
+pub fn parse_duration(input: &str) -> Option<u64> {
+    let text: String = input
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if text.is_empty() {
+        return None;
+    }
+
+    let secs = if text.chars().all(|c| c.is_ascii_digit()) {
+        text.parse::<u64>().ok()?.checked_mul(60)?
+    } else {
+        let mut total: u64 = 0;
+        let mut number = String::new();
+        for c in text.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+            let unit = match c {
+                'h' => 3600,
+                'm' => 60,
+                's' => 1,
+                _ => return None,
+            };
+            let value: u64 = number.parse().ok()?;
+            total = total.checked_add(value.checked_mul(unit)?)?;
+            number.clear();
+        }
+        // Trailing digits without a unit ("1h30") are minutes
+        if !number.is_empty() {
+            total = total.checked_add(number.parse::<u64>().ok()?.checked_mul(60)?)?;
+        }
+        total
+    };
+
+    (MIN_TIMER_SECS..=MAX_TIMER_SECS)
+        .contains(&secs)
+        .then_some(secs)
+}
+
+/// Format seconds the way the duration prompt accepts them (e.g. "1h30m", "45m", "1m30s")
+pub fn format_duration_input(secs: u64) -> String {
+    let (hours, mins, rest) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    let mut text = String::new();
+    if hours > 0 {
+        text.push_str(&format!("{}h", hours));
+    }
+    if mins > 0 {
+        text.push_str(&format!("{}m", mins));
+    }
+    if rest > 0 || text.is_empty() {
+        text.push_str(&format!("{}s", rest));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn durations_parse_in_any_unit_mix() {
+        assert_eq!(parse_duration("45"), Some(45 * 60));
+        assert_eq!(parse_duration("1h30m"), Some(90 * 60));
+        assert_eq!(parse_duration("1H 5M"), Some(65 * 60));
+        assert_eq!(parse_duration("1h30"), Some(90 * 60));
+        assert_eq!(parse_duration("90s"), Some(90));
+        assert_eq!(parse_duration("2m30s"), Some(150));
+    }
+
+    #[test]
+    fn durations_outside_the_timer_range_or_malformed_are_rejected() {
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("59s"), None);
+        assert_eq!(parse_duration("3h1s"), None);
+        assert_eq!(parse_duration("h"), None);
+        assert_eq!(parse_duration("10x"), None);
+        assert_eq!(parse_duration("99999999999999999999"), None);
+        assert_eq!(parse_duration("3h"), Some(MAX_TIMER_SECS));
+    }
+
+    #[test]
+    fn formatted_durations_read_back_the_same() {
+        assert_eq!(format_duration_input(0), "0s");
+        assert_eq!(format_duration_input(45 * 60), "45m");
+        assert_eq!(format_duration_input(90), "1m30s");
+        assert_eq!(format_duration_input(3600 + 5), "1h5s");
+        for secs in [60, 90, 25 * 60, 5400, 2 * 3600 + 61, MAX_TIMER_SECS] {
+            assert_eq!(parse_duration(&format_duration_input(secs)), Some(secs));
+        }
+    }
+
+    fn app(config: Config) -> App {
+        App::from_parts(
+            config,
+            SessionHistory::default(),
+            TagStore::default(),
+            ArchiveStore::default(),
+            TaskStore::default(),
+            Keymap::default(),
+            Theme::default(),
+        )
+    }
+
+    /// A running snapshot saved an hour before `now()`
+    fn snapshot(timer_mode: &str, timer_secs: u64, remaining_secs: u64) -> TimerSnapshot {
+        TimerSnapshot {
+            saved_at: now() - chrono::Duration::hours(1),
+            timer_mode: timer_mode.to_string(),
+            timer_secs,
+            timer_state: "work".to_string(),
+            remaining_secs,
+            session_count: 0,
+            is_running: true,
+            selected_task: None,
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        "2026-10-01T12:00:00Z".parse().unwrap()
+    }
+
+    fn missed(app: &App) -> Vec<(TimerState, u64, DateTime<Utc>)> {
+        app.missed_phases
+            .iter()
+            .map(|p| (p.state, p.duration_secs, p.ended_at))
+            .collect()
+    }
+
+    #[test]
+    fn phases_that_ran_out_while_closed_are_queued_and_work_waits() {
+        let mut app = app(Config {
+            auto_start_breaks: true,
+            ..Config::default()
+        });
+        app.catch_up(snapshot("pomodoro", 0, 10 * 60), now());
+
+        // Work ran out 50 minutes ago and its break 45 minutes ago; the next work waits
+        let saved_at = now() - chrono::Duration::hours(1);
+        assert_eq!(
+            missed(&app),
+            [
+                (
+                    TimerState::Work,
+                    25 * 60,
+                    saved_at + chrono::Duration::minutes(10)
+                ),
+                (
+                    TimerState::ShortBreak,
+                    5 * 60,
+                    saved_at + chrono::Duration::minutes(15)
+                ),
+            ]
+        );
+        assert_eq!(app.timer_state, TimerState::Work);
+        assert_eq!(app.remaining_time, Duration::from_secs(25 * 60));
+        assert!(app.is_paused);
+        assert_eq!(app.input_mode, InputMode::ConfirmResume);
+    }
+
+    #[test]
+    fn breaks_wait_too_unless_they_auto_start() {
+        let mut app = app(Config::default());
+        app.catch_up(snapshot("pomodoro", 0, 10 * 60), now());
+
+        assert_eq!(missed(&app).len(), 1);
+        assert_eq!(app.timer_state, TimerState::ShortBreak);
+        assert_eq!(app.remaining_time, Duration::from_secs(5 * 60));
+        assert!(app.is_paused);
+    }
+
+    #[test]
+    fn a_phase_still_running_keeps_counting_down() {
+        let mut app = app(Config::default());
+        let five_minutes_ago = |remaining_secs| TimerSnapshot {
+            saved_at: now() - chrono::Duration::minutes(5),
+            ..snapshot("pomodoro", 0, remaining_secs)
+        };
+        app.catch_up(five_minutes_ago(20 * 60), now());
+
+        assert!(app.missed_phases.is_empty());
+        assert_eq!(app.remaining_time, Duration::from_secs(15 * 60));
+        assert!(!app.is_paused);
+        assert_eq!(app.input_mode, InputMode::Normal);
+
+        // Time left over from a longer profile is capped at the current work length
+        let mut app = self::app(Config::default());
+        app.catch_up(five_minutes_ago(90 * 60), now());
+        assert_eq!(app.remaining_time, Duration::from_secs(20 * 60));
+    }
+
+    #[test]
+    fn timers_end_once_and_stopwatches_keep_counting() {
+        let mut app = app(Config::default());
+        app.catch_up(snapshot("timer", 45 * 60, 20 * 60), now());
+        assert_eq!(missed(&app).len(), 1);
+        assert_eq!(app.timer_mode, TimerMode::Timer(45 * 60));
+        assert_eq!(app.remaining_time, Duration::from_secs(45 * 60));
+        assert!(app.is_paused);
+
+        let mut app = self::app(Config::default());
+        app.catch_up(snapshot("stopwatch", 0, 10 * 60), now());
+        assert!(app.missed_phases.is_empty());
+        assert_eq!(app.elapsed_time, Duration::from_secs(70 * 60));
+        assert!(!app.is_paused);
     }
 }
@@ -0,0 +1,177 @@
+use crate::app::TimerState;
+use crate::persistence::config::Config;
+use anyhow::Result;
+use rodio::source::SineWave;
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Source};
+use std::fs::File;
+use std::io::BufReader;
+use std::time::Duration;
+
+/// A playable sound, already converted to f32 samples
+pub type SoundSource = Box<dyn Source<Item = f32> + Send>;
+
+/// Destination for rendered sounds
+pub trait AudioOutput {
+    fn play(&mut self, source: SoundSource) -> Result<()>;
+}
+
+/// Plays sounds on the system's default output device
+pub struct RodioOutput {
+    // Dropping the stream stops playback, so it must live as long as the handle
+    _stream: OutputStream,
+    handle: OutputStreamHandle,
+}
+
+impl RodioOutput {
+    pub fn try_default() -> Result<Self> {
+        let (stream, handle) = OutputStream::try_default()?;
+        Ok(Self {
+            _stream: stream,
+            handle,
+        })
+    }
+}
+
+impl AudioOutput for RodioOutput {
+    fn play(&mut self, source: SoundSource) -> Result<()> {
+        self.handle.play_raw(source)?;
+        Ok(())
+    }
+}
+
+/// Swallows every sound (no device available, or running headless)
+#[derive(Debug, Default)]
+pub struct NullOutput;
+
+impl AudioOutput for NullOutput {
+    fn play(&mut self, _source: SoundSource) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Plays a chime when a phase ends
+pub struct SoundPlayer {
+    output: Box<dyn AudioOutput>,
+}
+
+impl SoundPlayer {
+    /// Use the default output device, falling back to silence if there is none
+    pub fn new() -> Self {
+        match RodioOutput::try_default() {
+            Ok(output) => Self::with_output(Box::new(output)),
+            Err(_) => Self::with_output(Box::new(NullOutput)),
+        }
+    }
+
+    pub fn with_output(output: Box<dyn AudioOutput>) -> Self {
+        Self { output }
+    }
+
+    /// Play the sound for the phase that just finished
+    pub fn play_for(&mut self, state: TimerState, config: &Config) {
+        if !config.sound_enabled || config.sound_volume == 0 {
+            return;
+        }
+
+        let volume = config.sound_volume.min(100) as f32 / 100.0;
+        let source = custom_sound(state, config).unwrap_or_else(|| chime(state));
+        let _ = self.output.play(Box::new(source.amplify(volume)));
+    }
+}
+
+impl Default for SoundPlayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Load the user's sound file for this phase, if one is configured and decodable
+fn custom_sound(state: TimerState, config: &Config) -> Option<SoundSource> {
+    let path = match state {
+        TimerState::Work => config.sound_work_file.as_ref(),
+        TimerState::ShortBreak => config.sound_short_break_file.as_ref(),
+        TimerState::LongBreak => config.sound_long_break_file.as_ref(),
+    }?;
+
+    let file = File::open(path).ok()?;
+    let decoder = Decoder::new(BufReader::new(file)).ok()?;
+    Some(Box::new(decoder.convert_samples()))
+}
+
+/// Notes of the built-in chimes as (frequency Hz, length ms)
+fn chime_notes(state: TimerState) -> &'static [(f32, u64)] {
+    match state {
+        // Rising C major arpeggio: work done, time to rest
+        TimerState::Work => &[(659.25, 160), (783.99, 160), (1046.50, 420)],
+        // Falling fifth: back to work
+        TimerState::ShortBreak => &[(783.99, 200), (523.25, 420)],
+        // Longer falling run after a long break
+        TimerState::LongBreak => &[(1046.50, 160), (783.99, 160), (659.25, 160), (523.25, 480)],
+    }
+}
+
+/// Synthesize the embedded chime for a phase
+fn chime(state: TimerState) -> SoundSource {
+    let notes = chime_notes(state).iter().map(|&(freq, ms)| {
+        let mut note = SineWave::new(freq).take_duration(Duration::from_millis(ms));
+        note.set_filter_fadeout();
+        note.fade_in(Duration::from_millis(8)).amplify(0.5)
+    });
+
+    Box::new(rodio::source::from_iter(notes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Keeps the sample count of every sound, which tells the chimes apart
+    struct Recorder(Rc<RefCell<Vec<usize>>>);
+
+    impl AudioOutput for Recorder {
+        fn play(&mut self, source: SoundSource) -> Result<()> {
+            self.0.borrow_mut().push(source.count());
+            Ok(())
+        }
+    }
+
+    fn played(state: TimerState, config: &Config) -> Vec<usize> {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut player = SoundPlayer::with_output(Box::new(Recorder(log.clone())));
+        player.play_for(state, config);
+        let played = log.borrow().clone();
+        played
+    }
+
+    #[test]
+    fn each_phase_plays_its_own_chime() {
+        let config = Config::default();
+        let states = [
+            TimerState::Work,
+            TimerState::ShortBreak,
+            TimerState::LongBreak,
+        ];
+        let lengths: Vec<usize> = states.iter().map(|&state| chime(state).count()).collect();
+        assert!(lengths[0] != lengths[1] && lengths[1] != lengths[2] && lengths[0] != lengths[2]);
+
+        for (state, length) in states.into_iter().zip(lengths) {
+            assert_eq!(played(state, &config), vec![length]);
+        }
+    }
+
+    #[test]
+    fn muted_or_silent_settings_play_nothing() {
+        let muted = Config {
+            sound_enabled: false,
+            ..Config::default()
+        };
+        let silent = Config {
+            sound_volume: 0,
+            ..Config::default()
+        };
+        assert!(played(TimerState::Work, &muted).is_empty());
+        assert!(played(TimerState::LongBreak, &silent).is_empty());
+    }
+}
@@ -0,0 +1,712 @@
+use crate::persistence::{
+    archive::ArchiveStore,
+    calendar::DayBoundary,
+    config::Config,
+    export::{self, ExportFilter, ExportFormat},
+    import::{self, ColumnMapping, DurationUnit, ImportFormat},
+    sessions::{Session, SessionHistory, SessionMode, SessionType},
+    streaks::StreakRules,
+    tags::TagStore,
+    tasks::{add_project, parse_task_input, TaskData, TaskSort, TaskStore},
+};
+use crate::ui::theme::Theme;
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A beautiful, minimal Terminal User Interface Pomodoro timer
+#[derive(Debug, Parser)]
+#[command(name = "pomo-tui", version, about)]
+pub struct Cli {
+    /// Run a command headlessly instead of opening the TUI
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run a work session in this terminal and record it when it ends
+    Start {
+        /// Work duration in minutes (defaults to the configured duration)
+        #[arg(long, value_name = "MINS")]
+        work: Option<u64>,
+        /// Task to credit the pomodoro to
+        #[arg(long)]
+        task: Option<String>,
+        /// Profile to use instead of the active one
+        #[arg(long)]
+        profile: Option<String>,
+    },
+    /// Print focus statistics and streaks
+    Stats,
+    /// Manage the task list
+    Tasks {
+        #[command(subcommand)]
+        action: TasksCommand,
+    },
+    /// Export session history as CSV, JSON Lines or iCalendar
+    Export {
+        /// csv, jsonl or ics (guessed from --output, otherwise csv)
+        #[arg(long, short)]
+        format: Option<String>,
+        /// File to write (defaults to stdout)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+        /// First day to include
+        #[arg(long, value_name = "YYYY-MM-DD")]
+        from: Option<String>,
+        /// Last day to include
+        #[arg(long, value_name = "YYYY-MM-DD")]
+        to: Option<String>,
+        /// Only sessions whose task contains this text
+        #[arg(long)]
+        task: Option<String>,
+        /// Only sessions whose task has this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Only sessions recorded under this profile
+        #[arg(long)]
+        profile: Option<String>,
+    },
+    /// Import sessions from another tool (CSV, Toggl CSV or JSON)
+    Import {
+        /// File to read
+        file: PathBuf,
+        /// csv, toggl or json (detected when omitted)
+        #[arg(long, short)]
+        format: Option<String>,
+        /// Map a session field to a CSV header, e.g. --column "start=Started At"
+        /// (fields: start, end, duration, task, type, note, completed)
+        #[arg(long = "column", value_name = "FIELD=HEADER")]
+        columns: Vec<String>,
+        /// Unit of a plain-number duration column: secs, mins or hours
+        #[arg(long, value_name = "UNIT")]
+        duration_unit: Option<String>,
+        /// Show what would be imported without saving
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Read or change configuration values
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TasksCommand {
+    /// Add a task (use #tag for tags)
+    Add {
+        /// List to add it to instead of the active one (created if needed)
+        #[arg(long, short)]
+        project: Option<String>,
+        #[arg(required = true, num_args = 1..)]
+        text: Vec<String>,
+    },
+    /// List the active list's tasks with their numbers
+    List,
+    /// Mark a task done by its number from `tasks list`
+    Done { number: usize },
+    /// Show the task lists
+    Projects,
+    /// Make a task list active, creating it if it doesn't exist
+    Switch { name: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Print one value, or the whole config when no key is given
+    Get { key: Option<String> },
+    /// Set a value (JSON literals such as 50, true or null are accepted)
+    Set { key: String, value: String },
+}
+
+/// Execute a headless command
+pub fn run(command: Command) -> Result<()> {
+    match command {
+        Command::Start {
+            work,
+            task,
+            profile,
+        } => start(work, task, profile),
+        Command::Stats => stats(),
+        Command::Tasks { action } => match action {
+            TasksCommand::Add { project, text } => tasks_add(&text.join(" "), project),
+            TasksCommand::List => tasks_list(),
+            TasksCommand::Done { number } => tasks_done(number),
+            TasksCommand::Projects => tasks_projects(),
+            TasksCommand::Switch { name } => tasks_switch(&name),
+        },
+        Command::Export {
+            format,
+            output,
+            from,
+            to,
+            task,
+            tag,
+            profile,
+        } => {
+            let filter = ExportFilter {
+                from: from.as_deref().map(export::parse_date).transpose()?,
+                to: to.as_deref().map(export::parse_date).transpose()?,
+                task,
+                tag: tag.map(|t| t.trim_start_matches('#').to_string()),
+                profile,
+                day: DayBoundary::from_config(&Config::peek()?),
+            };
+            export_sessions(format.as_deref(), output, &filter)
+        }
+        Command::Import {
+            file,
+            format,
+            columns,
+            duration_unit,
+            dry_run,
+        } => {
+            let format = format
+                .map(|f| {
+                    ImportFormat::from_name(&f)
+                        .with_context(|| format!("unknown import format: {}", f))
+                })
+                .transpose()?;
+            let mut mapping = ColumnMapping::default();
+            for pair in &columns {
+                mapping.set(pair)?;
+            }
+            mapping.duration_unit = duration_unit
+                .map(|u| {
+                    DurationUnit::from_name(&u)
+                        .with_context(|| format!("unknown duration unit: {}", u))
+                })
+                .transpose()?;
+            import_sessions(&file, format, &mapping, dry_run)
+        }
+        Command::Config { action } => match action {
+            ConfigCommand::Get { key } => config_get(key.as_deref()),
+            ConfigCommand::Set { key, value } => config_set(&key, &value),
+        },
+    }
+}
+
+fn format_duration(secs: u64) -> String {
+    let hours = secs / 3600;
+    let mins = (secs % 3600) / 60;
+    if hours > 0 {
+        format!("{}h {}m", hours, mins)
+    } else {
+        format!("{}m", mins)
+    }
+}
+
+fn start(work: Option<u64>, task: Option<String>, profile: Option<String>) -> Result<()> {
+    let mut config = Config::load()?;
+    if let Some(name) = profile {
+        if !config.use_profile(&name) {
+            bail!("unknown profile: {}", name);
+        }
+    }
+    let mins = work.unwrap_or(config.work_duration_mins);
+    if !(1..=180).contains(&mins) {
+        bail!("work duration must be between 1 and 180 minutes");
+    }
+    // Like a custom timer in the TUI, another length records focus time but earns no pomodoro
+    let mode = if mins == config.work_duration_mins {
+        SessionMode::Pomodoro
+    } else {
+        SessionMode::Timer
+    };
+
+    // Credit an existing task when the name matches, otherwise just label the session
+    let mut store = TaskStore::load()?;
+    let task_index = task.as_ref().and_then(|name| {
+        store
+            .tasks
+            .iter()
+            .position(|t| t.name.eq_ignore_ascii_case(name))
+    });
+    let task_name = match task_index {
+        Some(i) => Some(store.tasks[i].name.clone()),
+        None => task,
+    };
+    let project = match task_index {
+        Some(i) => store.tasks[i].project.clone(),
+        None => store.active_project.clone(),
+    };
+
+    let total = Duration::from_secs(mins * 60);
+    let started = Instant::now();
+    let interactive = io::stdout().is_terminal();
+    let mut stdout = io::stdout();
+
+    if !interactive {
+        println!("Started {} min work session", mins);
+    }
+
+    loop {
+        let remaining = total.saturating_sub(started.elapsed());
+        if interactive {
+            let secs = remaining.as_secs();
+            let label = task_name
+                .as_deref()
+                .map(|n| format!("  {}", n))
+                .unwrap_or_default();
+            write!(stdout, "\r🍅 {:02}:{:02}{}  ", secs / 60, secs % 60, label)?;
+            stdout.flush()?;
+        }
+        if remaining.is_zero() {
+            break;
+        }
+        thread::sleep(remaining.min(Duration::from_millis(250)));
+    }
+
+    if interactive {
+        // Ring the terminal bell
+        writeln!(stdout, "\x07")?;
+    }
+
+    let mut history = load_history(&config)?;
+    let mut session = Session::new(SessionType::Work, total.as_secs(), task_name.clone());
+    if let Some(i) = task_index {
+        let task = &store.tasks[i];
+        session.link_task(task.id, &task.name, &task.tags);
+    }
+    session.profile = Some(config.active_profile.clone());
+    session.project = Some(project);
+    session.mode = mode;
+    let earns_pomodoro = session.earns_pomodoro();
+    history.add(session);
+    history.save()?;
+
+    if let Some(i) = task_index.filter(|_| earns_pomodoro) {
+        store.tasks[i].spend_pomodoro(None);
+        store.save()?;
+    }
+
+    match task_name {
+        Some(name) => println!("✓ Work session complete ({} min) — {}", mins, name),
+        None => println!("✓ Work session complete ({} min)", mins),
+    }
+    Ok(())
+}
+
+fn stats() -> Result<()> {
+    let config = Config::peek()?;
+    let history = peek_history(&config)?;
+
+    println!(
+        "Today      {}  ({}/{} 🍅)",
+        format_duration(history.today_focus_secs()),
+        history.today_pomodoro_count(),
+        config.daily_goal_pomodoros
+    );
+    println!("This week  {}", format_duration(history.week_focus_secs()));
+    println!(
+        "All time   {}  ({} sessions)",
+        format_duration(history.total_focus_secs()),
+        history.sessions().len()
+    );
+    if let Some(rate) = history.completion_rate() {
+        println!("Completed  {:.0}% of work sessions", rate * 100.0);
+    }
+    println!(
+        "Streak     🔥 {} days  (best {})",
+        history.current_streak(),
+        history.longest_streak()
+    );
+    let projects = history.week_focus_by_project();
+    if projects.iter().any(|(project, _)| project.is_some()) {
+        println!();
+        for (project, secs) in projects {
+            let name = project.unwrap_or_else(|| "(no list)".to_string());
+            println!("{:<10} {}", name, format_duration(secs));
+        }
+    }
+
+    // Tasks are shown under their current name, even if they were renamed since
+    let tasks = history.week_focus_by_task();
+    if !tasks.is_empty() {
+        let store = TaskStore::peek()?;
+        println!();
+        println!("Top tasks this week");
+        for (id, name, secs) in tasks.into_iter().take(5) {
+            let name = id
+                .and_then(|id| store.tasks.iter().find(|t| t.id == id))
+                .map_or(name, |task| task.name.clone());
+            println!("  {:<28} {}", name, format_duration(secs));
+        }
+    }
+    let tags = history.week_focus_by_tag();
+    if !tags.is_empty() {
+        println!();
+        let tags: Vec<String> = tags
+            .into_iter()
+            .map(|(tag, secs)| format!("#{} {}", tag, format_duration(secs)))
+            .collect();
+        println!("Tags       {}", tags.join("  "));
+    }
+    println!();
+
+    let days = history.last_7_days_focus();
+    let max_mins = days.iter().map(|(_, s)| s / 60).max().unwrap_or(0).max(1);
+    for (day, secs) in days {
+        let mins = secs / 60;
+        let width = (mins * 30 / max_mins) as usize;
+        println!(
+            "{}  {:<30} {}",
+            day,
+            "█".repeat(width),
+            format_duration(secs)
+        );
+    }
+    Ok(())
+}
+
+fn tasks_add(text: &str, project: Option<String>) -> Result<()> {
+    let input = parse_task_input(text);
+    if input.name.trim().is_empty() {
+        bail!("task name cannot be empty");
+    }
+
+    if !input.tags.is_empty() {
+        let mut tag_store = TagStore::load()?;
+        tag_store.record_usage(&input.tags);
+    }
+
+    let name = input.name.clone();
+    let mut store = TaskStore::load()?;
+    let project = match project {
+        Some(name) => {
+            add_project(&mut store.projects, &name);
+            name
+        }
+        None => store.active_project.clone(),
+    };
+    let mut task = TaskData::new(String::new(), &project);
+    task.apply_input(input);
+    store.tasks.push(task);
+    store.save()?;
+
+    if project == store.active_project {
+        println!("Added task {}: {}", store.active_tasks().count(), name);
+    } else {
+        println!("Added task to {}: {}", project, name);
+    }
+    Ok(())
+}
+
+fn tasks_list() -> Result<()> {
+    let store = TaskStore::peek()?;
+    if store.active_tasks().next().is_none() {
+        println!("No tasks in {} yet", store.active_project);
+        return Ok(());
+    }
+
+    let today = chrono::Local::now().date_naive();
+    println!("{}", store.active_project);
+    for (i, (_, task)) in store.active_tasks().enumerate() {
+        let checkbox = if task.completed { "✓" } else { "○" };
+        let mut line = format!("{:>3}. {} ", i + 1, checkbox);
+        if !task.priority.marker().is_empty() {
+            line.push_str(&format!("{} ", task.priority.marker()));
+        }
+        line.push_str(&task.name);
+        match task.estimate {
+            Some(estimate) => {
+                line.push_str(&format!(" {}/{} 🍅", task.total_pomodoros(), estimate))
+            }
+            None if task.total_pomodoros() > 0 => {
+                line.push_str(&format!(" 🍅×{}", task.total_pomodoros()))
+            }
+            None => {}
+        }
+        if let Some(due) = task.due {
+            let overdue = !task.completed && due < today;
+            line.push_str(&format!(
+                " due {}{}",
+                due.format("%Y-%m-%d"),
+                if overdue { " (overdue)" } else { "" }
+            ));
+        }
+        for tag in &task.tags {
+            line.push_str(&format!(" #{}", tag));
+        }
+        println!("{}", line);
+        if let Some(description) = &task.description {
+            println!("       {}", description);
+        }
+        for subtask in &task.subtasks {
+            let checkbox = if subtask.completed { "✓" } else { "○" };
+            let mut line = format!("       {} {}", checkbox, subtask.name);
+            if subtask.pomodoros_spent > 0 {
+                line.push_str(&format!(" 🍅×{}", subtask.pomodoros_spent));
+            }
+            println!("{}", line);
+        }
+    }
+    Ok(())
+}
+
+fn tasks_done(number: usize) -> Result<()> {
+    let mut store = TaskStore::load()?;
+    let index = number
+        .checked_sub(1)
+        .and_then(|n| store.active_tasks().nth(n))
+        .map(|(i, _)| i)
+        .with_context(|| format!("no task number {} in {}", number, store.active_project))?;
+    let task = &mut store.tasks[index];
+
+    if task.completed {
+        println!("✓ Already done: {}", task.name);
+        return Ok(());
+    }
+    task.set_completed(true);
+    let name = task.name.clone();
+    store.save()?;
+
+    println!("✓ Completed: {}", name);
+    Ok(())
+}
+
+fn tasks_projects() -> Result<()> {
+    let store = TaskStore::peek()?;
+    for project in &store.projects {
+        let tasks: Vec<&TaskData> = store
+            .tasks
+            .iter()
+            .filter(|t| &t.project == project)
+            .collect();
+        let open = tasks.iter().filter(|t| !t.completed).count();
+        let marker = if *project == store.active_project {
+            "●"
+        } else {
+            " "
+        };
+        println!(
+            "{} {:<20} {} open, {} total",
+            marker,
+            project,
+            open,
+            tasks.len()
+        );
+    }
+    Ok(())
+}
+
+fn tasks_switch(name: &str) -> Result<()> {
+    let name = name.trim();
+    if name.is_empty() {
+        bail!("list name cannot be empty");
+    }
+    let mut store = TaskStore::load()?;
+    let created = !store.projects.iter().any(|p| p == name);
+    store.use_project(name);
+    store.save()?;
+
+    if created {
+        println!("Created and switched to {}", name);
+    } else {
+        println!("Switched to {}", name);
+    }
+    Ok(())
+}
+
+fn export_sessions(
+    format: Option<&str>,
+    output: Option<PathBuf>,
+    filter: &ExportFilter,
+) -> Result<()> {
+    let guessed = output
+        .as_ref()
+        .and_then(|path| path.extension())
+        .and_then(|ext| ext.to_str());
+    let format = match format.or(guessed) {
+        Some(name) => ExportFormat::from_name(name)
+            .with_context(|| format!("unknown export format: {}", name))?,
+        None => ExportFormat::Csv,
+    };
+
+    let history = peek_history(&Config::peek()?)?;
+    let sessions = filter.apply(history.sessions());
+
+    match output {
+        Some(path) => {
+            let count = export::export_to_file(format, &sessions, &path)?;
+            eprintln!("Exported {} sessions to {}", count, path.display());
+        }
+        None => export::write_sessions(format, &sessions, io::stdout().lock())?,
+    }
+    Ok(())
+}
+
+/// Session history with days counted the way the config says, for commands that save it
+///
+/// Sessions that only name their task are linked to it first (see `SessionHistory::link_tasks`);
+/// the links are written out with the next save.
+fn load_history(config: &Config) -> Result<SessionHistory> {
+    Ok(linked_history(
+        SessionHistory::load()?,
+        config,
+        &known_tasks()?,
+    ))
+}
+
+/// Open and archived tasks, the ones sessions can be linked to
+fn known_tasks() -> Result<Vec<TaskData>> {
+    let mut tasks = TaskStore::load()?.tasks;
+    tasks.extend(
+        ArchiveStore::load()?
+            .entries
+            .into_iter()
+            .map(|entry| entry.task),
+    );
+    Ok(tasks)
+}
+
+/// Session history for read-only commands: like `load_history`, but nothing on disk is touched
+fn peek_history(config: &Config) -> Result<SessionHistory> {
+    let mut tasks = TaskStore::peek()?.tasks;
+    tasks.extend(
+        ArchiveStore::peek()?
+            .entries
+            .into_iter()
+            .map(|entry| entry.task),
+    );
+    Ok(linked_history(SessionHistory::peek()?, config, &tasks))
+}
+
+fn linked_history(
+    mut history: SessionHistory,
+    config: &Config,
+    tasks: &[TaskData],
+) -> SessionHistory {
+    if let Some(warning) = history.load_warning.take() {
+        eprintln!("Warning: {}", warning);
+    }
+    history.use_config(config);
+    history.link_tasks(tasks);
+    history
+}
+
+fn import_sessions(
+    file: &Path,
+    format: Option<ImportFormat>,
+    mapping: &ColumnMapping,
+    dry_run: bool,
+) -> Result<()> {
+    let (mut history, tasks) = if dry_run {
+        (peek_history(&Config::peek()?)?, Vec::new())
+    } else {
+        let tasks = known_tasks()?;
+        (
+            linked_history(SessionHistory::load()?, &Config::load()?, &tasks),
+            tasks,
+        )
+    };
+    let report = import::import_file(file, format, mapping, history.sessions())?;
+
+    let focus_secs: u64 = report
+        .sessions
+        .iter()
+        .filter(|s| s.session_type == SessionType::Work)
+        .map(|s| s.duration_secs)
+        .sum();
+    println!(
+        "{} sessions ({} focus), {} duplicates skipped, {} unreadable rows",
+        report.sessions.len(),
+        format_duration(focus_secs),
+        report.duplicates,
+        report.skipped
+    );
+
+    if dry_run || report.sessions.is_empty() {
+        return Ok(());
+    }
+
+    // Imported sessions may only name their task, so they are linked once they are in
+    history.merge(report.sessions);
+    history.link_tasks(&tasks);
+    history.save()?;
+    println!(
+        "Streak     🔥 {} days  (best {})",
+        history.current_streak(),
+        history.longest_streak()
+    );
+    Ok(())
+}
+
+fn config_get(key: Option<&str>) -> Result<()> {
+    let config = serde_json::to_value(Config::peek()?)?;
+    match key {
+        None => println!("{}", serde_json::to_string_pretty(&config)?),
+        Some(key) => {
+            let value = config
+                .get(key)
+                .with_context(|| format!("unknown config key: {}", key))?;
+            match value {
+                serde_json::Value::String(s) => println!("{}", s),
+                other => println!("{}", other),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn config_set(key: &str, value: &str) -> Result<()> {
+    let current = serde_json::to_value(Config::load()?)?;
+    if current.get(key).is_none() {
+        bail!("unknown config key: {}", key);
+    }
+
+    // Try the value as a JSON literal first, then as a plain string
+    let candidates = serde_json::from_str(value)
+        .into_iter()
+        .chain(std::iter::once(serde_json::Value::String(
+            value.to_string(),
+        )));
+    let mut config = candidates
+        .filter_map(|candidate| {
+            let mut updated = current.clone();
+            updated[key] = candidate;
+            serde_json::from_value::<Config>(updated).ok()
+        })
+        .next()
+        .with_context(|| format!("invalid value for {}: {}", key, value))?;
+
+    // Keep the durations and the active profile in step
+    match key {
+        "active_profile" => {
+            let name = config.active_profile.clone();
+            if !config.use_profile(&name) {
+                bail!("unknown profile: {}", name);
+            }
+        }
+        "work_duration_mins"
+        | "short_break_mins"
+        | "long_break_mins"
+        | "sessions_before_long_break" => {
+            config.sync_active_profile();
+        }
+        "time_zone" | "day_start_hour" => {
+            DayBoundary::new(config.time_zone.as_deref(), config.day_start_hour)?;
+        }
+        "streak_freeze_weekdays" | "streak_freeze_dates" => {
+            StreakRules::new(&config)?;
+        }
+        "task_sort" => {
+            TaskSort::from_name(&config.task_sort)
+                .with_context(|| format!("unknown task sort: {}", value))?;
+        }
+        "theme" if !Theme::available().contains(&config.theme) => {
+            bail!("unknown theme: {}", value);
+        }
+        _ => {}
+    }
+    config.check()?;
+    config.save()?;
+
+    println!("{} = {}", key, value);
+    Ok(())
+}
@@ -0,0 +1,510 @@
+use anyhow::{Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+/// Something the user can trigger from the keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Help,
+    QuickCapture,
+    SwitchView(u8),
+    ToggleTimer,
+    ResetTimer,
+    Skip,
+    ToggleMode,
+    SetDuration,
+    PickProfile,
+    PickProject,
+    Export,
+    ToggleFocus,
+    SwitchPane,
+    Up,
+    Down,
+    AddTask,
+    EditTask,
+    DeleteTask,
+    ClearCompleted,
+    ToggleDone,
+    MoveTaskUp,
+    MoveTaskDown,
+    Search,
+    CycleSort,
+    Undo,
+    Redo,
+    ShowArchive,
+    ManageTags,
+    AddSubtask,
+    Expand,
+    Collapse,
+    Back,
+    Increase,
+    Decrease,
+}
+
+impl Action {
+    const NAMED: [(&'static str, Action); 34] = [
+        ("quit", Action::Quit),
+        ("help", Action::Help),
+        ("quick_capture", Action::QuickCapture),
+        ("toggle_timer", Action::ToggleTimer),
+        ("reset_timer", Action::ResetTimer),
+        ("skip", Action::Skip),
+        ("toggle_mode", Action::ToggleMode),
+        ("set_duration", Action::SetDuration),
+        ("pick_profile", Action::PickProfile),
+        ("pick_project", Action::PickProject),
+        ("export", Action::Export),
+        ("toggle_focus", Action::ToggleFocus),
+        ("switch_pane", Action::SwitchPane),
+        ("up", Action::Up),
+        ("down", Action::Down),
+        ("add_task", Action::AddTask),
+        ("edit_task", Action::EditTask),
+        ("delete_task", Action::DeleteTask),
+        ("clear_completed", Action::ClearCompleted),
+        ("toggle_done", Action::ToggleDone),
+        ("move_task_up", Action::MoveTaskUp),
+        ("move_task_down", Action::MoveTaskDown),
+        ("search", Action::Search),
+        ("cycle_sort", Action::CycleSort),
+        ("undo", Action::Undo),
+        ("redo", Action::Redo),
+        ("show_archive", Action::ShowArchive),
+        ("manage_tags", Action::ManageTags),
+        ("add_subtask", Action::AddSubtask),
+        ("expand", Action::Expand),
+        ("collapse", Action::Collapse),
+        ("back", Action::Back),
+        ("increase", Action::Increase),
+        ("decrease", Action::Decrease),
+    ];
+
+    /// Parse the name used in keys.toml (e.g. "toggle_timer", "switch_view_2")
+    pub fn from_name(name: &str) -> Option<Self> {
+        if let Some(n) = name.strip_prefix("switch_view_") {
+            return n
+                .parse()
+                .ok()
+                .filter(|n| (1..=3).contains(n))
+                .map(Action::SwitchView);
+        }
+        Self::NAMED
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, a)| *a)
+    }
+
+    /// Short description for the help overlay
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Help => "Show help",
+            Action::QuickCapture => "Quick capture",
+            Action::SwitchView(_) => "Switch view",
+            Action::ToggleTimer => "Start / Pause",
+            Action::ResetTimer => "Reset timer",
+            Action::Skip => "Skip to next",
+            Action::ToggleMode => "Toggle mode",
+            Action::SetDuration => "Set timer length",
+            Action::PickProfile => "Profiles",
+            Action::PickProject => "Task lists",
+            Action::Export => "Export sessions",
+            Action::ToggleFocus => "Focus mode",
+            Action::SwitchPane => "Switch pane",
+            Action::Up | Action::Down => "Navigate",
+            Action::AddTask => "Add task",
+            Action::EditTask => "Edit task",
+            Action::DeleteTask => "Delete task",
+            Action::ClearCompleted => "Clear completed",
+            Action::ToggleDone => "Toggle done",
+            Action::MoveTaskUp | Action::MoveTaskDown => "Move task",
+            Action::Search => "Search tasks",
+            Action::CycleSort => "Sort tasks",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::ShowArchive => "Archive",
+            Action::ManageTags => "Manage tags",
+            Action::AddSubtask => "Add subtask",
+            Action::Expand | Action::Collapse => "Expand / collapse",
+            Action::Back => "Back",
+            Action::Increase | Action::Decrease => "Adjust",
+        }
+    }
+}
+
+/// Where a binding applies; view sections fall back to `Global`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyContext {
+    Global,
+    Timer,
+    Dashboard,
+    Settings,
+}
+
+impl KeyContext {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "global" => Some(KeyContext::Global),
+            "timer" => Some(KeyContext::Timer),
+            "dashboard" => Some(KeyContext::Dashboard),
+            "settings" => Some(KeyContext::Settings),
+            _ => None,
+        }
+    }
+}
+
+/// A key plus modifiers, normalized so that `J` and `shift-j` compare equal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let code = match code {
+            // The character already carries the shift state
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            KeyCode::BackTab => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            other => other,
+        };
+        Self { code, modifiers }
+    }
+
+    /// Parse strings like "q", "space", "ctrl-n", "shift-j", "alt-enter", "f5"
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = text;
+
+        // A lone "-" is a key, not a separator
+        while let Some((prefix, tail)) = rest.split_once('-').filter(|(_, tail)| !tail.is_empty()) {
+            match prefix.to_ascii_lowercase().as_str() {
+                "ctrl" | "c" => modifiers |= KeyModifiers::CONTROL,
+                "alt" | "a" | "m" => modifiers |= KeyModifiers::ALT,
+                "shift" | "s" => modifiers |= KeyModifiers::SHIFT,
+                _ => return None,
+            }
+            rest = tail;
+        }
+
+        let code = match rest.to_ascii_lowercase().as_str() {
+            "space" => KeyCode::Char(' '),
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            lower => {
+                let mut chars = rest.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => KeyCode::F(lower.strip_prefix('f')?.parse().ok()?),
+                }
+            }
+        };
+
+        Some(Self::new(code, modifiers))
+    }
+
+    /// Compact label for footers and the help overlay
+    pub fn display(&self) -> String {
+        let key = match self.code {
+            KeyCode::Char(' ') => "␣".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::Enter => "⏎".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::BackTab => "S-Tab".to_string(),
+            KeyCode::Backspace => "⌫".to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::F(n) => format!("F{}", n),
+            other => format!("{:?}", other),
+        };
+
+        let mut label = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push_str("C-");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("M-");
+        }
+        label.push_str(&key);
+        label
+    }
+}
+
+impl From<KeyEvent> for KeyBinding {
+    fn from(event: KeyEvent) -> Self {
+        Self::new(event.code, event.modifiers)
+    }
+}
+
+/// Active key bindings: defaults merged with `~/.config/pomo-tui/keys.toml`
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: HashMap<KeyContext, Vec<(Action, Vec<KeyBinding>)>>,
+    /// Set when keys.toml named contexts, actions or keys that don't exist
+    pub load_warning: Option<String>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        use Action::*;
+        use KeyContext::*;
+
+        let mut keymap = Self {
+            bindings: HashMap::new(),
+            load_warning: None,
+        };
+
+        keymap.bind(Global, Quit, &["q", "Q", "ctrl-c"]);
+        keymap.bind(Global, SwitchView(1), &["1"]);
+        keymap.bind(Global, SwitchView(2), &["2"]);
+        keymap.bind(Global, SwitchView(3), &["3"]);
+        keymap.bind(Global, QuickCapture, &["/"]);
+
+        keymap.bind(Timer, ToggleTimer, &["space"]);
+        keymap.bind(Timer, ResetTimer, &["r", "R"]);
+        keymap.bind(Timer, Skip, &["n", "N"]);
+        keymap.bind(Timer, ToggleMode, &["m", "M"]);
+        keymap.bind(Timer, SetDuration, &["t", "T"]);
+        keymap.bind(Timer, PickProfile, &["p", "P"]);
+        keymap.bind(Timer, PickProject, &["o", "O"]);
+        keymap.bind(Timer, Increase, &["+", "="]);
+        keymap.bind(Timer, Decrease, &["-"]);
+        keymap.bind(Timer, ToggleFocus, &["f", "F"]);
+        keymap.bind(Timer, SwitchPane, &["tab"]);
+        keymap.bind(Timer, Down, &["j", "down"]);
+        keymap.bind(Timer, Up, &["k", "up"]);
+        keymap.bind(Timer, AddTask, &["a", "A"]);
+        keymap.bind(Timer, EditTask, &["e", "E"]);
+        keymap.bind(Timer, DeleteTask, &["d", "D"]);
+        keymap.bind(Timer, ClearCompleted, &["c", "C"]);
+        keymap.bind(Timer, ToggleDone, &["enter"]);
+        keymap.bind(Timer, MoveTaskDown, &["J"]);
+        keymap.bind(Timer, MoveTaskUp, &["K"]);
+        keymap.bind(Timer, Search, &["ctrl-f"]);
+        keymap.bind(Timer, CycleSort, &["z", "Z"]);
+        keymap.bind(Timer, Undo, &["u"]);
+        keymap.bind(Timer, Redo, &["ctrl-r", "U"]);
+        keymap.bind(Timer, ShowArchive, &["v", "V"]);
+        keymap.bind(Timer, ManageTags, &["#"]);
+        keymap.bind(Timer, AddSubtask, &["s", "S"]);
+        keymap.bind(Timer, Expand, &["l", "right"]);
+        keymap.bind(Timer, Collapse, &["h", "left"]);
+        keymap.bind(Timer, Help, &["?"]);
+        keymap.bind(Timer, Back, &["esc"]);
+
+        keymap.bind(Dashboard, PickProfile, &["p", "P"]);
+        keymap.bind(Dashboard, Export, &["e", "E"]);
+        keymap.bind(Dashboard, Back, &["esc"]);
+
+        keymap.bind(Settings, Down, &["j", "down"]);
+        keymap.bind(Settings, Up, &["k", "up"]);
+        keymap.bind(Settings, Decrease, &["left", "h"]);
+        keymap.bind(Settings, Increase, &["right", "l", "enter"]);
+        keymap.bind(Settings, Back, &["esc"]);
+
+        keymap
+    }
+}
+
+impl Keymap {
+    fn file_path() -> Result<PathBuf> {
+        Ok(crate::persistence::config_dir()?.join("keys.toml"))
+    }
+
+    /// Load the default keymap with user overrides applied
+    ///
+    /// keys.toml has one table per context, mapping action names to keys:
+    ///
+    /// ```toml
+    /// [global]
+    /// quit = ["ctrl-q"]
+    ///
+    /// [timer]
+    /// toggle_timer = ["space", "p"]
+    /// skip = ["ctrl-n"]
+    /// ```
+    ///
+    /// A keys.toml that isn't valid TOML is an error; unknown names are skipped and
+    /// listed in `load_warning`.
+    pub fn load() -> Result<Self> {
+        let mut keymap = Self::default();
+        let path = Self::file_path()?;
+        if path.exists() {
+            let contents = fs::read_to_string(&path)?;
+            keymap
+                .apply(&contents)
+                .with_context(|| format!("could not read {}", path.display()))?;
+        }
+        Ok(keymap)
+    }
+
+    /// Apply keys.toml overrides on top of the current bindings
+    fn apply(&mut self, contents: &str) -> Result<()> {
+        let overrides: BTreeMap<String, BTreeMap<String, Vec<String>>> = toml::from_str(contents)?;
+
+        let mut unknown = Vec::new();
+        for (context_name, actions) in overrides {
+            let Some(context) = KeyContext::from_name(&context_name) else {
+                unknown.push(format!("[{}]", context_name));
+                continue;
+            };
+            for (action_name, keys) in actions {
+                let Some(action) = Action::from_name(&action_name) else {
+                    unknown.push(format!("{}.{}", context_name, action_name));
+                    continue;
+                };
+                for key in keys.iter().filter(|k| KeyBinding::parse(k).is_none()) {
+                    unknown.push(format!("{}.{} = \"{}\"", context_name, action_name, key));
+                }
+                let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+                self.bind(context, action, &keys);
+            }
+        }
+
+        if !unknown.is_empty() {
+            self.load_warning = Some(format!("keys.toml: ignored unknown {}", unknown.join(", ")));
+        }
+        Ok(())
+    }
+
+    /// Replace the keys for an action in a context, taking them away from any other action there
+    fn bind(&mut self, context: KeyContext, action: Action, keys: &[&str]) {
+        let keys: Vec<KeyBinding> = keys.iter().filter_map(|k| KeyBinding::parse(k)).collect();
+        let entries = self.bindings.entry(context).or_default();
+
+        for (_, existing) in entries.iter_mut().filter(|(a, _)| *a != action) {
+            existing.retain(|k| !keys.contains(k));
+        }
+        match entries.iter_mut().find(|(a, _)| *a == action) {
+            Some((_, existing)) => *existing = keys,
+            None => entries.push((action, keys)),
+        }
+    }
+
+    /// Resolve a key press, checking the view first and then global bindings
+    pub fn action(&self, context: KeyContext, key: KeyEvent) -> Option<Action> {
+        let binding = KeyBinding::from(key);
+        [context, KeyContext::Global]
+            .iter()
+            .filter_map(|c| self.bindings.get(c))
+            .flat_map(|entries| entries.iter())
+            .find(|(_, keys)| keys.contains(&binding))
+            .map(|(action, _)| *action)
+    }
+
+    /// Keys bound to an action (view bindings first, then global)
+    pub fn keys(&self, context: KeyContext, action: Action) -> Vec<KeyBinding> {
+        [context, KeyContext::Global]
+            .iter()
+            .filter_map(|c| self.bindings.get(c))
+            .flat_map(|entries| entries.iter())
+            .filter(|(a, _)| *a == action)
+            .flat_map(|(_, keys)| keys.iter().copied())
+            .collect()
+    }
+
+    /// Label of the primary key for an action, e.g. "␣" or "C-n"
+    pub fn hint(&self, context: KeyContext, action: Action) -> String {
+        self.keys(context, action)
+            .first()
+            .map(|k| k.display())
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    /// Actions bound in a context, in definition order, with their key labels
+    pub fn help_entries(&self, context: KeyContext) -> Vec<(String, &'static str)> {
+        let mut entries: Vec<(String, &'static str)> = Vec::new();
+
+        for ctx in [context, KeyContext::Global] {
+            for (action, keys) in self.bindings.get(&ctx).into_iter().flatten() {
+                if keys.is_empty() {
+                    continue;
+                }
+                // Merge paired actions (navigate, switch view) into one line
+                let label = keys[0].display();
+                let description = action.description();
+                match entries.iter_mut().find(|(_, d)| *d == description) {
+                    Some((existing, _)) => {
+                        existing.push_str(" / ");
+                        existing.push_str(&label);
+                    }
+                    None => entries.push((label, description)),
+                }
+            }
+        }
+
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn remapping_a_used_key_takes_it_from_the_old_action() {
+        let mut keymap = Keymap::default();
+        keymap
+            .apply("[timer]\nskip = [\"r\"]\ndown = [\"n\"]\n")
+            .unwrap();
+
+        assert_eq!(
+            keymap.action(KeyContext::Timer, press('r')),
+            Some(Action::Skip)
+        );
+        assert_eq!(
+            keymap.action(KeyContext::Timer, press('n')),
+            Some(Action::Down)
+        );
+        assert_eq!(
+            keymap.keys(KeyContext::Timer, Action::ResetTimer),
+            [KeyBinding::parse("R").unwrap()]
+        );
+        assert_eq!(keymap.action(KeyContext::Timer, press('j')), None);
+        assert!(keymap.load_warning.is_none());
+    }
+
+    #[test]
+    fn bad_keys_toml_is_reported() {
+        let mut keymap = Keymap::default();
+        assert!(keymap.apply("[timer\nskip = 3").is_err());
+
+        keymap
+            .apply("[timer]\nskipp = [\"x\"]\nskip = [\"hyper-x\"]\n[nowhere]\n")
+            .unwrap();
+        let warning = keymap.load_warning.unwrap();
+        assert!(warning.contains("timer.skipp"));
+        assert!(warning.contains("hyper-x"));
+        assert!(warning.contains("[nowhere]"));
+    }
+}
@@ -1,9 +1,15 @@
 mod app;
+#[cfg(feature = "audio")]
+mod audio;
+mod cli;
+mod keymap;
 mod persistence;
 mod ui;
 
 use anyhow::Result;
 use app::App;
+use clap::Parser;
+use cli::Cli;
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind},
     execute,
@@ -13,6 +19,15 @@ use ratatui::prelude::*;
 use std::{io, panic, time::Duration};
 
 fn main() -> Result<()> {
+    // Subcommands run headlessly and never touch the alternate screen
+    let cli = Cli::parse();
+    if let Some(command) = cli.command {
+        return cli::run(command);
+    }
+
+    // Load before taking over the terminal so a load error prints normally
+    let mut app = App::new()?;
+
     // Set up panic hook to restore terminal on crash
     let original_hook = panic::take_hook();
     panic::set_hook(Box::new(move |panic_info| {
@@ -23,8 +38,7 @@ fn main() -> Result<()> {
     // Initialize terminal
     let mut terminal = setup_terminal()?;
 
-    // Create app and run
-    let mut app = App::new();
+    // Run the app
     let result = run_app(&mut terminal, &mut app);
 
     // Restore terminal
@@ -61,7 +75,7 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()>
             if let Event::Key(key) = event::read()? {
                 // Only handle key press events (not release)
                 if key.kind == KeyEventKind::Press {
-                    app.handle_key(key.code);
+                    app.handle_key(key);
                 }
             }
         }
@@ -0,0 +1,82 @@
+use super::tasks::TaskData;
+use super::Schema;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Why a task left the task list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveReason {
+    /// Completed and swept away by "clear completed"
+    Cleared,
+    Deleted,
+}
+
+impl ArchiveReason {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ArchiveReason::Cleared => "cleared",
+            ArchiveReason::Deleted => "deleted",
+        }
+    }
+}
+
+/// A task that was cleared or deleted, kept so it can be restored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedTask {
+    pub task: TaskData,
+    pub archived_at: DateTime<Utc>,
+    pub reason: ArchiveReason,
+}
+
+/// Cleared and deleted tasks, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ArchiveStore {
+    pub entries: Vec<ArchivedTask>,
+}
+
+impl ArchiveStore {
+    fn file_path() -> Result<PathBuf> {
+        Ok(super::data_dir()?.join("archive.json"))
+    }
+
+    pub fn load() -> Result<Self> {
+        let path = Self::file_path()?;
+
+        if path.exists() {
+            super::read_versioned(&path, Schema::Archive)
+        } else {
+            Ok(ArchiveStore::default())
+        }
+    }
+
+    /// Read the archive without writing anything
+    pub fn peek() -> Result<Self> {
+        let path = Self::file_path()?;
+        if path.exists() {
+            super::peek_versioned(&path, Schema::Archive)
+        } else {
+            Ok(ArchiveStore::default())
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::file_path()?;
+        super::save_versioned(&path, Schema::Archive, self)
+    }
+
+    pub fn add(&mut self, task: TaskData, reason: ArchiveReason) {
+        self.entries.push(ArchivedTask {
+            task,
+            archived_at: Utc::now(),
+            reason,
+        });
+    }
+
+    /// Take a task back out of the archive
+    pub fn take(&mut self, index: usize) -> Option<TaskData> {
+        (index < self.entries.len()).then(|| self.entries.remove(index).task)
+    }
+}
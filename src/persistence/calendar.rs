@@ -0,0 +1,118 @@
+use super::config::Config;
+use anyhow::{bail, Result};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+
+/// Where one day ends and the next begins
+///
+/// Days are counted in the configured IANA time zone (the system's local zone when
+/// unset) and start at `start_hour`, so with a 4am start a 1am session still belongs
+/// to the previous day.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DayBoundary {
+    time_zone: Option<Tz>,
+    start_hour: u8,
+}
+
+impl DayBoundary {
+    pub fn new(time_zone: Option<&str>, start_hour: u8) -> Result<Self> {
+        if start_hour > 23 {
+            bail!("day start hour must be between 0 and 23");
+        }
+        let time_zone = match time_zone {
+            Some(name) => match name.parse::<Tz>() {
+                Ok(tz) => Some(tz),
+                Err(_) => bail!(
+                    "unknown time zone: {} (use an IANA name such as Europe/Berlin)",
+                    name
+                ),
+            },
+            None => None,
+        };
+        Ok(Self {
+            time_zone,
+            start_hour,
+        })
+    }
+
+    /// Boundary from the config, falling back to local midnight if it is invalid
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.time_zone.as_deref(), config.day_start_hour).unwrap_or_default()
+    }
+
+    fn wall_clock(&self, time: DateTime<Utc>) -> NaiveDateTime {
+        match self.time_zone {
+            Some(tz) => time.with_timezone(&tz).naive_local(),
+            None => time.with_timezone(&Local).naive_local(),
+        }
+    }
+
+    /// The day a moment belongs to
+    pub fn day_of(&self, time: DateTime<Utc>) -> NaiveDate {
+        (self.wall_clock(time) - chrono::Duration::hours(self.start_hour as i64)).date()
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.day_of(Utc::now())
+    }
+
+    /// Monday of the current week
+    pub fn week_start(&self) -> NaiveDate {
+        let today = self.today();
+        today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn days_are_counted_in_the_configured_zone() {
+        let tokyo = DayBoundary::new(Some("Asia/Tokyo"), 0).unwrap();
+        let los_angeles = DayBoundary::new(Some("America/Los_Angeles"), 0).unwrap();
+
+        // 16:00 UTC is already the next morning in Tokyo, still the same morning in LA
+        assert_eq!(tokyo.day_of(utc(2026, 3, 1, 16, 0)), date(2026, 3, 2));
+        assert_eq!(los_angeles.day_of(utc(2026, 3, 1, 16, 0)), date(2026, 3, 1));
+        // 05:00 UTC is the previous evening in LA
+        assert_eq!(los_angeles.day_of(utc(2026, 3, 1, 5, 0)), date(2026, 2, 28));
+    }
+
+    #[test]
+    fn late_sessions_belong_to_the_day_before_the_start_hour() {
+        let berlin = DayBoundary::new(Some("Europe/Berlin"), 4).unwrap();
+
+        // 01:30 and 03:59 in Berlin (UTC+1 in winter) still count for the previous day
+        assert_eq!(berlin.day_of(utc(2026, 1, 15, 0, 30)), date(2026, 1, 14));
+        assert_eq!(berlin.day_of(utc(2026, 1, 15, 2, 59)), date(2026, 1, 14));
+        assert_eq!(berlin.day_of(utc(2026, 1, 15, 3, 0)), date(2026, 1, 15));
+
+        // Across the spring clock change 03:30 local is UTC+2, still before the 4am start
+        assert_eq!(berlin.day_of(utc(2026, 3, 29, 1, 30)), date(2026, 3, 28));
+        assert_eq!(berlin.day_of(utc(2026, 3, 29, 2, 0)), date(2026, 3, 29));
+    }
+
+    #[test]
+    fn unknown_zones_and_hours_are_rejected() {
+        assert!(DayBoundary::new(Some("Mars/Olympus"), 0).is_err());
+        assert!(DayBoundary::new(Some("UTC"), 24).is_err());
+
+        let config = Config {
+            time_zone: Some("Mars/Olympus".to_string()),
+            day_start_hour: 4,
+            ..Config::default()
+        };
+        assert_eq!(DayBoundary::from_config(&config), DayBoundary::default());
+    }
+}
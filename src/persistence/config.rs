@@ -1,10 +1,86 @@
-use anyhow::Result;
+use super::Schema;
+use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
-use std::fs;
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
 
+/// How many custom Timer-mode lengths to remember
+const MAX_RECENT_TIMERS: usize = 5;
+
+// Limits the settings screen keeps values within
+pub const WORK_MINS: RangeInclusive<i64> = 1..=120;
+pub const BREAK_MINS: RangeInclusive<i64> = 1..=60;
+pub const SESSIONS_BEFORE_LONG: RangeInclusive<i64> = 1..=10;
+pub const DAILY_GOAL: RangeInclusive<i64> = 1..=20;
+pub const STREAK_MIN_POMODOROS: RangeInclusive<i64> = 1..=10;
+pub const DAY_START_HOUR: RangeInclusive<i64> = 0..=23;
+pub const HIDE_HINTS_SECS: RangeInclusive<i64> = 0..=10;
+pub const SOUND_VOLUME: RangeInclusive<i64> = 0..=100;
+
+fn check_range(key: &str, value: i64, range: RangeInclusive<i64>) -> Result<()> {
+    if !range.contains(&value) {
+        bail!(
+            "{} must be between {} and {}",
+            key,
+            range.start(),
+            range.end()
+        );
+    }
+    Ok(())
+}
+
+/// A named set of Pomodoro durations
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub work_duration_mins: u64,
+    pub short_break_mins: u64,
+    pub long_break_mins: u64,
+    pub sessions_before_long_break: u8,
+}
+
+impl Profile {
+    fn new(name: &str, work: u64, short_break: u64, long_break: u64, sessions: u8) -> Self {
+        Self {
+            name: name.to_string(),
+            work_duration_mins: work,
+            short_break_mins: short_break,
+            long_break_mins: long_break,
+            sessions_before_long_break: sessions,
+        }
+    }
+
+    /// Short summary such as "50/10/30 ×3"
+    pub fn summary(&self) -> String {
+        format!(
+            "{}/{}/{} ×{}",
+            self.work_duration_mins,
+            self.short_break_mins,
+            self.long_break_mins,
+            self.sessions_before_long_break
+        )
+    }
+
+    /// Fail on durations the settings screen would not allow
+    pub fn check(&self) -> Result<()> {
+        check_range(
+            "work_duration_mins",
+            self.work_duration_mins as i64,
+            WORK_MINS,
+        )?;
+        check_range("short_break_mins", self.short_break_mins as i64, BREAK_MINS)?;
+        check_range("long_break_mins", self.long_break_mins as i64, BREAK_MINS)?;
+        check_range(
+            "sessions_before_long_break",
+            self.sessions_before_long_break as i64,
+            SESSIONS_BEFORE_LONG,
+        )
+    }
+}
+
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     // Timer durations (in minutes)
     pub work_duration_mins: u64,
@@ -12,13 +88,33 @@ pub struct Config {
     pub long_break_mins: u64,
     pub sessions_before_long_break: u8,
 
+    // Profiles (the active one mirrors the durations above)
+    pub profiles: Vec<Profile>,
+    pub active_profile: String,
+
     // Mode settings
     pub default_mode: String,
     pub auto_start_breaks: bool,
+    /// Custom Timer-mode lengths in seconds, most recent first
+    pub recent_timer_secs: Vec<u64>,
+    /// Stopwatch breaks last this fraction of the focus time (5 = a fifth)
+    pub flow_break_divisor: u64,
 
     // Goals & Streaks
     pub daily_goal_pomodoros: u8,
     pub show_streak: bool,
+    /// Completed pomodoros a day needs to extend the streak
+    pub streak_min_pomodoros: u8,
+    /// Only days that reach the daily goal extend the streak
+    pub streak_needs_goal: bool,
+    /// Weekdays that never break a streak, e.g. ["sat", "sun"]
+    pub streak_freeze_weekdays: Vec<String>,
+    /// Dates or ranges ("2026-12-24..2027-01-02") that never break a streak
+    pub streak_freeze_dates: Vec<String>,
+    /// IANA time zone for day boundaries (system local time when unset)
+    pub time_zone: Option<String>,
+    /// Hour (0-23) at which a new day begins
+    pub day_start_hour: u8,
 
     // Appearance
     pub breathing_enabled: bool,
@@ -28,8 +124,21 @@ pub struct Config {
     // Focus behavior
     pub focus_mode_on_start: bool,
 
+    // Tasks
+    /// Task pane order: manual, pomodoros, created or tag
+    pub task_sort: String,
+    /// Forget tags unused for this many days (0 keeps them); tags on a task or with a color stay
+    pub tag_retention_days: u32,
+
     // Notifications
     pub notifications_enabled: bool,
+
+    // Sound (requires the `audio` feature)
+    pub sound_enabled: bool,
+    pub sound_volume: u8,
+    pub sound_work_file: Option<String>,
+    pub sound_short_break_file: Option<String>,
+    pub sound_long_break_file: Option<String>,
 }
 
 impl Default for Config {
@@ -39,15 +148,36 @@ impl Default for Config {
             short_break_mins: 5,
             long_break_mins: 15,
             sessions_before_long_break: 4,
+            profiles: vec![
+                Profile::new("classic", 25, 5, 15, 4),
+                Profile::new("deep-work", 50, 10, 30, 3),
+                Profile::new("study", 90, 20, 30, 2),
+            ],
+            active_profile: "classic".to_string(),
             default_mode: "pomodoro".to_string(),
             auto_start_breaks: false,
+            recent_timer_secs: Vec::new(),
+            flow_break_divisor: 5,
             daily_goal_pomodoros: 8,
             show_streak: true,
+            streak_min_pomodoros: 1,
+            streak_needs_goal: false,
+            streak_freeze_weekdays: Vec::new(),
+            streak_freeze_dates: Vec::new(),
+            time_zone: None,
+            day_start_hour: 0,
             breathing_enabled: false,
             hide_hints_after_secs: 3,
             theme: "dark".to_string(),
             focus_mode_on_start: false,
+            task_sort: "manual".to_string(),
+            tag_retention_days: 30,
             notifications_enabled: true,
+            sound_enabled: true,
+            sound_volume: 70,
+            sound_work_file: None,
+            sound_short_break_file: None,
+            sound_long_break_file: None,
         }
     }
 }
@@ -61,9 +191,9 @@ impl Config {
         let path = Self::file_path()?;
 
         if path.exists() {
-            let contents = fs::read_to_string(&path)?;
             // Use serde's default for missing fields
-            let config: Config = serde_json::from_str(&contents).unwrap_or_default();
+            let mut config: Config = super::read_versioned(&path, Schema::Config)?;
+            config.sync_active_profile();
             Ok(config)
         } else {
             let config = Config::default();
@@ -72,10 +202,158 @@ impl Config {
         }
     }
 
-    pub fn save(&self) -> Result<()> {
+    /// Read the config without writing anything (a missing file gives the defaults)
+    pub fn peek() -> Result<Self> {
         let path = Self::file_path()?;
-        let contents = serde_json::to_string_pretty(self)?;
-        fs::write(path, contents)?;
+        if path.exists() {
+            let mut config: Config = super::peek_versioned(&path, Schema::Config)?;
+            config.sync_active_profile();
+            Ok(config)
+        } else {
+            Ok(Config::default())
+        }
+    }
+
+    /// Switch to a named profile, copying its durations (false if there is no such profile)
+    pub fn use_profile(&mut self, name: &str) -> bool {
+        let Some(profile) = self.profiles.iter().find(|p| p.name == name).cloned() else {
+            return false;
+        };
+
+        self.work_duration_mins = profile.work_duration_mins;
+        self.short_break_mins = profile.short_break_mins;
+        self.long_break_mins = profile.long_break_mins;
+        self.sessions_before_long_break = profile.sessions_before_long_break;
+        self.active_profile = profile.name;
+        true
+    }
+
+    /// Store the current durations in the active profile, creating it if needed
+    pub fn sync_active_profile(&mut self) {
+        let current = Profile::new(
+            &self.active_profile,
+            self.work_duration_mins,
+            self.short_break_mins,
+            self.long_break_mins,
+            self.sessions_before_long_break,
+        );
+
+        match self
+            .profiles
+            .iter_mut()
+            .find(|p| p.name == self.active_profile)
+        {
+            Some(profile) => *profile = current,
+            None => self.profiles.push(current),
+        }
+    }
+
+    /// Fail on values the settings screen would not allow
+    pub fn check(&self) -> Result<()> {
+        Profile::new(
+            &self.active_profile,
+            self.work_duration_mins,
+            self.short_break_mins,
+            self.long_break_mins,
+            self.sessions_before_long_break,
+        )
+        .check()?;
+        check_range(
+            "daily_goal_pomodoros",
+            self.daily_goal_pomodoros as i64,
+            DAILY_GOAL,
+        )?;
+        check_range(
+            "streak_min_pomodoros",
+            self.streak_min_pomodoros as i64,
+            STREAK_MIN_POMODOROS,
+        )?;
+        check_range("day_start_hour", self.day_start_hour as i64, DAY_START_HOUR)?;
+        check_range(
+            "hide_hints_after_secs",
+            self.hide_hints_after_secs as i64,
+            HIDE_HINTS_SECS,
+        )?;
+        check_range("sound_volume", self.sound_volume as i64, SOUND_VOLUME)?;
+        for profile in &self.profiles {
+            profile
+                .check()
+                .with_context(|| format!("profile {}", profile.name))?;
+        }
         Ok(())
     }
+
+    /// Move a Timer-mode length to the front of the recent list
+    pub fn remember_timer_secs(&mut self, secs: u64) {
+        self.recent_timer_secs.retain(|&s| s != secs);
+        self.recent_timer_secs.insert(0, secs);
+        self.recent_timer_secs.truncate(MAX_RECENT_TIMERS);
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::file_path()?;
+        super::save_versioned(&path, Schema::Config, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn durations(config: &Config) -> (u64, u64, u64, u8) {
+        (
+            config.work_duration_mins,
+            config.short_break_mins,
+            config.long_break_mins,
+            config.sessions_before_long_break,
+        )
+    }
+
+    #[test]
+    fn switching_profiles_copies_their_durations() {
+        let mut config = Config::default();
+        assert!(config.use_profile("deep-work"));
+        assert_eq!(config.active_profile, "deep-work");
+        assert_eq!(durations(&config), (50, 10, 30, 3));
+
+        assert!(!config.use_profile("Deep-Work"));
+        assert_eq!(config.active_profile, "deep-work");
+        assert_eq!(durations(&config), (50, 10, 30, 3));
+    }
+
+    #[test]
+    fn edited_durations_are_kept_in_the_active_profile() {
+        let mut config = Config {
+            work_duration_mins: 30,
+            ..Config::default()
+        };
+        config.sync_active_profile();
+        config.use_profile("study");
+        config.use_profile("classic");
+        assert_eq!(durations(&config), (30, 5, 15, 4));
+
+        // A profile that went missing is recreated from the current durations
+        config.active_profile = "evening".to_string();
+        config.sync_active_profile();
+        assert!(config.use_profile("evening"));
+        assert_eq!(config.profiles.len(), 4);
+        assert_eq!(config.profiles[3].summary(), "30/5/15 ×4");
+    }
+
+    #[test]
+    fn profiles_outside_the_settings_ranges_are_rejected() {
+        assert!(Config::default().check().is_ok());
+
+        let mut config = Config::default();
+        config.profiles[2].work_duration_mins = 0;
+        let error = config.check().unwrap_err();
+        assert!(format!("{:#}", error).starts_with("profile study"));
+
+        let config = Config {
+            sessions_before_long_break: 0,
+            ..Config::default()
+        };
+        assert!(config.check().is_err());
+    }
 }
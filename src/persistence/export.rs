@@ -0,0 +1,412 @@
+use super::calendar::DayBoundary;
+use super::sessions::{Session, SessionType};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// File formats sessions can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Jsonl,
+    Ics,
+}
+
+impl ExportFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "jsonl" | "ndjson" => Some(ExportFormat::Jsonl),
+            "ics" | "ical" => Some(ExportFormat::Ics),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Jsonl => "jsonl",
+            ExportFormat::Ics => "ics",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            ExportFormat::Csv => ExportFormat::Jsonl,
+            ExportFormat::Jsonl => ExportFormat::Ics,
+            ExportFormat::Ics => ExportFormat::Csv,
+        }
+    }
+}
+
+/// Which sessions to export
+///
+/// Dates are inclusive and follow the configured day boundary. Task matches are case-insensitive
+/// substrings; tags are the ones the task had when the session was recorded, and `#work` also
+/// matches nested tags such as `work/backend`.
+#[derive(Debug, Clone, Default)]
+pub struct ExportFilter {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub task: Option<String>,
+    pub tag: Option<String>,
+    pub profile: Option<String>,
+    pub day: DayBoundary,
+}
+
+impl ExportFilter {
+    /// Parse a one-line query such as "from:2026-10-01 to:2026-10-15 #work report"
+    pub fn parse(query: &str) -> Result<Self> {
+        let mut filter = ExportFilter::default();
+        let mut words = Vec::new();
+
+        for token in query.split_whitespace() {
+            if let Some(date) = token.strip_prefix("from:") {
+                filter.from = Some(parse_date(date)?);
+            } else if let Some(date) = token.strip_prefix("to:") {
+                filter.to = Some(parse_date(date)?);
+            } else if let Some(profile) = token.strip_prefix("profile:") {
+                filter.profile = Some(profile.to_string());
+            } else if let Some(task) = token.strip_prefix("task:") {
+                words.push(task);
+            } else if token.starts_with('#') && token.len() > 1 {
+                filter.tag = Some(token[1..].to_string());
+            } else {
+                words.push(token);
+            }
+        }
+
+        if !words.is_empty() {
+            filter.task = Some(words.join(" "));
+        }
+        Ok(filter)
+    }
+
+    /// Whether a session passes the filter
+    pub fn matches(&self, session: &Session) -> bool {
+        let date = self.day.day_of(session.timestamp);
+        if self.from.is_some_and(|from| date < from) || self.to.is_some_and(|to| date > to) {
+            return false;
+        }
+
+        if let Some(profile) = &self.profile {
+            if session.profile.as_deref() != Some(profile.as_str()) {
+                return false;
+            }
+        }
+
+        if let Some(task) = &self.task {
+            let task_name = session.task_name.as_deref().unwrap_or("").to_lowercase();
+            if !task_name.contains(&task.to_lowercase()) {
+                return false;
+            }
+        }
+
+        if let Some(tag) = &self.tag {
+            let mut tags = session
+                .task_tags
+                .iter()
+                .chain(session.shares.iter().flat_map(|s| &s.task_tags));
+            if !tags.any(|t| super::tags::is_within(t, tag)) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Sessions that pass the filter, oldest first
+    pub fn apply<'a>(&self, sessions: &'a [Session]) -> Vec<&'a Session> {
+        let mut matched: Vec<&Session> = sessions.iter().filter(|s| self.matches(s)).collect();
+        matched.sort_by_key(|s| s.timestamp);
+        matched
+    }
+}
+
+/// Parse a YYYY-MM-DD date
+pub fn parse_date(text: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .with_context(|| format!("invalid date (use YYYY-MM-DD): {}", text))
+}
+
+/// When a session started (sessions are stamped when they end)
+fn started_at(session: &Session) -> DateTime<Utc> {
+    session.timestamp - chrono::Duration::seconds(session.duration_secs as i64)
+}
+
+/// Write sessions in the given format
+pub fn write_sessions(format: ExportFormat, sessions: &[&Session], out: impl Write) -> Result<()> {
+    match format {
+        ExportFormat::Csv => write_csv(sessions, out),
+        ExportFormat::Jsonl => write_jsonl(sessions, out),
+        ExportFormat::Ics => write_ics(sessions, out),
+    }
+}
+
+fn write_csv(sessions: &[&Session], out: impl Write) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record([
+        "id",
+        "start",
+        "end",
+        "type",
+        "duration_mins",
+        "completed",
+        "interruption",
+        "task",
+        "tags",
+        "profile",
+        "project",
+        "note",
+    ])?;
+
+    for session in sessions {
+        writer.write_record([
+            session.id.to_string(),
+            started_at(session).with_timezone(&Local).to_rfc3339(),
+            session.timestamp.with_timezone(&Local).to_rfc3339(),
+            session.session_type.as_str().to_string(),
+            format!("{:.1}", session.duration_secs as f64 / 60.0),
+            session.completed.to_string(),
+            session
+                .interruption
+                .map(|i| i.label().to_string())
+                .unwrap_or_default(),
+            session.task_name.clone().unwrap_or_default(),
+            session.task_tags.join(" "),
+            session.profile.clone().unwrap_or_default(),
+            session.project.clone().unwrap_or_default(),
+            session.note.clone().unwrap_or_default(),
+        ])?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_jsonl(sessions: &[&Session], mut out: impl Write) -> Result<()> {
+    for session in sessions {
+        serde_json::to_writer(&mut out, session)?;
+        out.write_all(b"\n")?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn write_ics(sessions: &[&Session], mut out: impl Write) -> Result<()> {
+    let stamp = ics_time(Utc::now());
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//pomo-tui//Session Export//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+
+    for session in sessions {
+        let summary = match (session.session_type, &session.task_name) {
+            (SessionType::Work, Some(task)) => format!("🍅 {}", task),
+            (SessionType::Work, None) => "🍅 Focus".to_string(),
+            (SessionType::ShortBreak, _) => "☕ Short break".to_string(),
+            (SessionType::LongBreak, _) => "🌴 Long break".to_string(),
+        };
+
+        let mut description = Vec::new();
+        if let Some(reason) = session.interruption {
+            description.push(format!("Ended early ({})", reason.label()));
+        }
+        if let Some(profile) = &session.profile {
+            description.push(format!("Profile: {}", profile));
+        }
+        if let Some(project) = &session.project {
+            description.push(format!("List: {}", project));
+        }
+        if let Some(note) = &session.note {
+            description.push(note.clone());
+        }
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}@pomo-tui", session.id));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART:{}", ics_time(started_at(session))));
+        lines.push(format!("DTEND:{}", ics_time(session.timestamp)));
+        lines.push(format!("SUMMARY:{}", ics_escape(&summary)));
+        if !description.is_empty() {
+            lines.push(format!(
+                "DESCRIPTION:{}",
+                ics_escape(&description.join("\n"))
+            ));
+        }
+        lines.push(format!(
+            "CATEGORIES:{}",
+            ics_escape(session.session_type.as_str())
+        ));
+        lines.push("TRANSP:OPAQUE".to_string());
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    for line in lines {
+        out.write_all(ics_fold(&line).as_bytes())?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn ics_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape a TEXT value (RFC 5545 §3.3.11)
+fn ics_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line at 75 octets and terminate it with CRLF
+fn ics_fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+/// Default location for exports: ~/.local/share/pomo-tui/exports/sessions-<time>.<ext>
+pub fn default_path(format: ExportFormat) -> Result<PathBuf> {
+    let dir = super::data_dir()?.join("exports");
+    fs::create_dir_all(&dir)?;
+    let name = format!(
+        "sessions-{}.{}",
+        Local::now().format("%Y%m%d-%H%M%S"),
+        format.extension()
+    );
+    Ok(dir.join(name))
+}
+
+/// Export sessions to a file, returning how many were written
+pub fn export_to_file(format: ExportFormat, sessions: &[&Session], path: &Path) -> Result<usize> {
+    if sessions.is_empty() {
+        bail!("no sessions match the filter");
+    }
+    let file =
+        File::create(path).with_context(|| format!("could not create {}", path.display()))?;
+    write_sessions(format, sessions, BufWriter::new(file))?;
+    Ok(sessions.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::sessions::TaskShare;
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+    use uuid::Uuid;
+
+    fn session(day: u32, hour: u32, task: &str, tags: &[&str]) -> Session {
+        Session {
+            timestamp: Utc.with_ymd_and_hms(2026, 10, day, hour, 0, 0).unwrap(),
+            task_tags: tags.iter().map(|t| t.to_string()).collect(),
+            profile: Some("deep".to_string()),
+            ..Session::new(SessionType::Work, 1500, Some(task.to_string()))
+        }
+    }
+
+    fn filter(query: &str) -> ExportFilter {
+        ExportFilter {
+            day: DayBoundary::new(Some("UTC"), 4).unwrap(),
+            ..ExportFilter::parse(query).unwrap()
+        }
+    }
+
+    #[test]
+    fn queries_split_into_dates_tags_profile_and_task_words() {
+        let filter = ExportFilter::parse(
+            "from:2026-10-01 #work Write task:report profile:deep to:2026-10-15",
+        )
+        .unwrap();
+        assert_eq!(filter.from, NaiveDate::from_ymd_opt(2026, 10, 1));
+        assert_eq!(filter.to, NaiveDate::from_ymd_opt(2026, 10, 15));
+        assert_eq!(filter.tag.as_deref(), Some("work"));
+        assert_eq!(filter.profile.as_deref(), Some("deep"));
+        assert_eq!(filter.task.as_deref(), Some("Write report"));
+
+        assert_eq!(
+            ExportFilter::parse("# ").unwrap().task.as_deref(),
+            Some("#")
+        );
+        assert!(ExportFilter::parse("from:yesterday").is_err());
+    }
+
+    #[test]
+    fn sessions_match_on_the_day_they_count_for() {
+        let range = filter("from:2026-10-02 to:2026-10-03");
+        // 02:00 on the 2nd still belongs to the 1st with a 4am day start
+        assert!(!range.matches(&session(2, 2, "Write report", &[])));
+        assert!(range.matches(&session(2, 4, "Write report", &[])));
+        assert!(range.matches(&session(4, 3, "Write report", &[])));
+        assert!(!range.matches(&session(4, 4, "Write report", &[])));
+    }
+
+    #[test]
+    fn tags_task_words_and_profiles_narrow_the_match() {
+        let report = session(5, 9, "Write Report", &["work/docs"]);
+        assert!(filter("#work report").matches(&report));
+        assert!(!filter("#wor").matches(&report));
+        assert!(!filter("#work/code").matches(&report));
+        assert!(!filter("report profile:light").matches(&report));
+        assert!(!filter("review").matches(&report));
+
+        // A session shared between tasks matches the tags of any of them
+        let mut shared = session(5, 10, "Inbox", &[]);
+        shared.shares.push(TaskShare {
+            task_id: Uuid::new_v4(),
+            subtask_id: None,
+            task_name: "Review".to_string(),
+            task_tags: vec!["work/code".to_string()],
+            secs: 600,
+        });
+        assert!(filter("#work").matches(&shared));
+
+        let sessions = [
+            shared.clone(),
+            report.clone(),
+            session(1, 9, "Old", &["work"]),
+        ];
+        let matched: Vec<&str> = filter("from:2026-10-05 #work")
+            .apply(&sessions)
+            .iter()
+            .map(|s| s.task_name.as_deref().unwrap())
+            .collect();
+        assert_eq!(matched, ["Write Report", "Inbox"]);
+    }
+
+    #[test]
+    fn calendar_text_is_escaped_and_folded() {
+        assert_eq!(ics_escape("a;b,c\\d\ne"), r"a\;b\,c\\d\ne");
+
+        let line = format!("SUMMARY:{}", "x".repeat(100));
+        let folded = ics_fold(&line);
+        let parts: Vec<&str> = folded.trim_end_matches("\r\n").split("\r\n").collect();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].len(), 75);
+        assert!(parts[1].starts_with(' '));
+        assert_eq!(folded.replace("\r\n ", ""), format!("{}\r\n", line));
+
+        // Multi-byte characters are never split across lines
+        let folded = ics_fold(&"é".repeat(40));
+        assert!(folded.split("\r\n").all(|part| part.len() <= 75));
+        assert_eq!(folded.replace("\r\n ", "").trim_end(), "é".repeat(40));
+        assert_eq!(ics_fold("short"), "short\r\n");
+    }
+}
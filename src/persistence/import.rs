@@ -0,0 +1,592 @@
+use super::sessions::{Session, SessionType};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Longest session an import accepts; longer rows are skipped as bad data
+const MAX_SESSION_SECS: u64 = 24 * 60 * 60;
+
+/// Source formats sessions can be imported from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// Any CSV with a header row, read through a `ColumnMapping`
+    Csv,
+    /// Toggl Track "Detailed report" CSV
+    Toggl,
+    /// JSON array, `{"sessions": [...]}` or JSON Lines
+    Json,
+}
+
+impl ImportFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "csv" => Some(ImportFormat::Csv),
+            "toggl" => Some(ImportFormat::Toggl),
+            "json" | "jsonl" | "ndjson" => Some(ImportFormat::Json),
+            _ => None,
+        }
+    }
+
+    /// Guess the format from the file extension and, for CSV, its header
+    pub fn detect(path: &Path, contents: &str) -> Self {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("json" | "jsonl" | "ndjson") => ImportFormat::Json,
+            _ => {
+                let header = contents.lines().next().unwrap_or("").to_ascii_lowercase();
+                if header.contains("start date") && header.contains("duration") {
+                    ImportFormat::Toggl
+                } else if header.trim_start().starts_with(['[', '{']) {
+                    ImportFormat::Json
+                } else {
+                    ImportFormat::Csv
+                }
+            }
+        }
+    }
+}
+
+/// Unit of a plain-number duration column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationUnit {
+    Secs,
+    Mins,
+    Hours,
+}
+
+impl DurationUnit {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "s" | "sec" | "secs" | "seconds" => Some(DurationUnit::Secs),
+            "m" | "min" | "mins" | "minutes" => Some(DurationUnit::Mins),
+            "h" | "hour" | "hours" => Some(DurationUnit::Hours),
+            _ => None,
+        }
+    }
+
+    fn secs(&self, value: f64) -> u64 {
+        let factor = match self {
+            DurationUnit::Secs => 1.0,
+            DurationUnit::Mins => 60.0,
+            DurationUnit::Hours => 3600.0,
+        };
+        (value * factor).round().max(0.0) as u64
+    }
+}
+
+/// Session fields a CSV column can map to
+const FIELDS: [&str; 7] = [
+    "start",
+    "end",
+    "duration",
+    "task",
+    "type",
+    "note",
+    "completed",
+];
+
+/// Which CSV column holds each session field
+///
+/// Unmapped fields are found by common header names, so pomo-tui's own CSV export
+/// imports without any mapping. Either `end` or `start` plus `duration` is required.
+#[derive(Debug, Clone, Default)]
+pub struct ColumnMapping {
+    columns: HashMap<&'static str, String>,
+    pub duration_unit: Option<DurationUnit>,
+}
+
+impl ColumnMapping {
+    /// Map a field to a header, from a "field=Header" pair
+    pub fn set(&mut self, pair: &str) -> Result<()> {
+        let (field, header) = pair
+            .split_once('=')
+            .with_context(|| format!("expected field=Header, got: {}", pair))?;
+        let field = FIELDS
+            .iter()
+            .find(|f| f.eq_ignore_ascii_case(field.trim()))
+            .with_context(|| {
+                format!(
+                    "unknown field {} (expected one of: {})",
+                    field,
+                    FIELDS.join(", ")
+                )
+            })?;
+        self.columns.insert(field, header.trim().to_string());
+        Ok(())
+    }
+
+    /// Resolve each field to a column index in this header row
+    fn resolve(&self, headers: &csv::StringRecord) -> Result<HashMap<&'static str, usize>> {
+        let find = |name: &str| {
+            headers
+                .iter()
+                .position(|h| h.trim().eq_ignore_ascii_case(name))
+        };
+
+        let mut resolved = HashMap::new();
+        for field in FIELDS {
+            let index = match self.columns.get(field) {
+                Some(header) => {
+                    Some(find(header).with_context(|| format!("no column named {}", header))?)
+                }
+                None => default_headers(field).iter().find_map(|h| find(h)),
+            };
+            if let Some(index) = index {
+                resolved.insert(field, index);
+            }
+        }
+
+        let has_start_and_duration =
+            resolved.contains_key("start") && resolved.contains_key("duration");
+        if !resolved.contains_key("end") && !has_start_and_duration {
+            bail!("need an end column, or start and duration columns (map them with field=Header)");
+        }
+        Ok(resolved)
+    }
+
+    /// Unit for the duration column: explicit, else guessed from its header
+    fn unit_for(&self, header: &str) -> DurationUnit {
+        let header = header.to_ascii_lowercase();
+        self.duration_unit.unwrap_or(if header.contains("sec") {
+            DurationUnit::Secs
+        } else if header.contains("hour") {
+            DurationUnit::Hours
+        } else {
+            DurationUnit::Mins
+        })
+    }
+}
+
+/// Header names recognised for each field when none is mapped
+fn default_headers(field: &str) -> &'static [&'static str] {
+    match field {
+        "start" => &["start", "start time", "started_at", "started", "begin"],
+        "end" => &["end", "end time", "ended_at", "ended", "stop", "timestamp"],
+        "duration" => &[
+            "duration_mins",
+            "duration_secs",
+            "duration",
+            "minutes",
+            "seconds",
+        ],
+        "task" => &["task", "task_name", "description", "title", "name"],
+        "type" => &["type", "session_type", "kind"],
+        "note" => &["note", "notes", "comment"],
+        "completed" => &["completed", "done"],
+        _ => &[],
+    }
+}
+
+/// Outcome of an import
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub sessions: Vec<Session>,
+    pub duplicates: usize,
+    pub skipped: usize,
+}
+
+/// Read sessions from a file, leaving out any already in `existing`
+pub fn import_file(
+    path: &Path,
+    format: Option<ImportFormat>,
+    mapping: &ColumnMapping,
+    existing: &[Session],
+) -> Result<ImportReport> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("could not read {}", path.display()))?;
+    let format = format.unwrap_or_else(|| ImportFormat::detect(path, &contents));
+
+    let parsed = match format {
+        ImportFormat::Csv => parse_csv(&contents, mapping)?,
+        ImportFormat::Toggl => parse_toggl(&contents)?,
+        ImportFormat::Json => parse_json(&contents)?,
+    };
+
+    let mut report = ImportReport::default();
+    let mut seen = DedupIndex::new(existing);
+    for session in parsed {
+        match session {
+            Some(session) if seen.insert(&session) => report.sessions.push(session),
+            Some(_) => report.duplicates += 1,
+            None => report.skipped += 1,
+        }
+    }
+    Ok(report)
+}
+
+/// Spots sessions recorded twice: same end time and duration, give or take a minute
+/// (exports round durations, and other tools keep different precision)
+struct DedupIndex {
+    buckets: HashSet<(i64, u64)>,
+}
+
+impl DedupIndex {
+    fn new(existing: &[Session]) -> Self {
+        let mut index = Self {
+            buckets: HashSet::new(),
+        };
+        for session in existing {
+            index.buckets.insert(Self::key(session));
+        }
+        index
+    }
+
+    fn key(session: &Session) -> (i64, u64) {
+        (
+            session.timestamp.timestamp() / 60,
+            session.duration_secs / 60,
+        )
+    }
+
+    /// Add a session, returning false if it duplicates one already seen
+    fn insert(&mut self, session: &Session) -> bool {
+        let (minute, mins) = Self::key(session);
+        let duplicate = (-1..=1).any(|dt| {
+            (-1..=1).any(|dd| {
+                mins.checked_add_signed(dd)
+                    .is_some_and(|m| self.buckets.contains(&(minute + dt, m)))
+            })
+        });
+        if !duplicate {
+            self.buckets.insert((minute, mins));
+        }
+        !duplicate
+    }
+}
+
+fn parse_csv(contents: &str, mapping: &ColumnMapping) -> Result<Vec<Option<Session>>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(contents.as_bytes());
+    let headers = reader.headers()?.clone();
+    let columns = mapping.resolve(&headers)?;
+    let unit = columns
+        .get("duration")
+        .map(|&i| mapping.unit_for(&headers[i]))
+        .unwrap_or(DurationUnit::Mins);
+
+    let mut sessions = Vec::new();
+    for record in reader.records() {
+        let Ok(record) = record else {
+            sessions.push(None);
+            continue;
+        };
+        let field = |name: &str| {
+            columns
+                .get(name)
+                .and_then(|&i| record.get(i))
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+        };
+
+        let start = field("start").and_then(parse_datetime);
+        let end = field("end").and_then(parse_datetime);
+        let duration = field("duration").and_then(|d| parse_duration(d, unit));
+        let completed = field("completed")
+            .map(|c| !matches!(c.to_ascii_lowercase().as_str(), "false" | "no" | "0"));
+
+        sessions.push(build_session(
+            start,
+            end,
+            duration,
+            field("type"),
+            field("task"),
+            field("note"),
+            completed.unwrap_or(true),
+        ));
+    }
+    Ok(sessions)
+}
+
+fn parse_toggl(contents: &str) -> Result<Vec<Option<Session>>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(contents.as_bytes());
+    let headers = reader.headers()?.clone();
+    let find = |name: &str| {
+        headers
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(name))
+    };
+
+    let start_date = find("Start date").context("Toggl CSV is missing 'Start date'")?;
+    let start_time = find("Start time").context("Toggl CSV is missing 'Start time'")?;
+    let duration = find("Duration").context("Toggl CSV is missing 'Duration'")?;
+    let description = find("Description");
+    let project = find("Project");
+    let tags = find("Tags");
+
+    let mut sessions = Vec::new();
+    for record in reader.records() {
+        let Ok(record) = record else {
+            sessions.push(None);
+            continue;
+        };
+        let get = |i: Option<usize>| {
+            i.and_then(|i| record.get(i))
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+        };
+
+        let start = match (get(Some(start_date)), get(Some(start_time))) {
+            (Some(date), Some(time)) => parse_datetime(&format!("{} {}", date, time)),
+            _ => None,
+        };
+        let duration = get(Some(duration)).and_then(|d| parse_duration(d, DurationUnit::Secs));
+
+        // Toggl has no notes; keep the project and tags so they aren't lost
+        let note = [
+            get(project).map(|p| format!("Project: {}", p)),
+            get(tags).map(|t| format!("Tags: {}", t)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" · ");
+        let task = get(description).or(get(project));
+
+        sessions.push(build_session(
+            start,
+            None,
+            duration,
+            None,
+            task,
+            Some(note.as_str()).filter(|n| !n.is_empty()),
+            true,
+        ));
+    }
+    Ok(sessions)
+}
+
+fn parse_json(contents: &str) -> Result<Vec<Option<Session>>> {
+    let records: Vec<Value> = match serde_json::from_str::<Value>(contents) {
+        Ok(Value::Array(items)) => items,
+        Ok(Value::Object(mut object)) => match object.remove("sessions") {
+            Some(Value::Array(items)) => items,
+            _ => vec![Value::Object(object)],
+        },
+        // Not a single document: treat it as JSON Lines
+        _ => contents
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| serde_json::from_str(l).unwrap_or(Value::Null))
+            .collect(),
+    };
+
+    Ok(records.iter().map(json_session).collect())
+}
+
+fn json_session(record: &Value) -> Option<Session> {
+    // Our own JSONL export round-trips exactly; anything else goes through the loose mapping
+    if let Ok(session) = serde_json::from_value::<Session>(record.clone()) {
+        return (1..=MAX_SESSION_SECS)
+            .contains(&session.duration_secs)
+            .then_some(session);
+    }
+
+    let object = record.as_object()?;
+    let get = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|n| object.get(*n))
+            .filter(|v| !v.is_null())
+    };
+    let text = |names: &[&str]| get(names).and_then(|v| v.as_str().map(str::to_string));
+
+    let datetime = |names: &[&str]| {
+        get(names).and_then(|v| match v {
+            Value::String(s) => parse_datetime(s),
+            Value::Number(n) => n
+                .as_i64()
+                .and_then(|secs| Utc.timestamp_opt(secs, 0).single()),
+            _ => None,
+        })
+    };
+    let start = datetime(&["start", "start_time", "started_at", "begin"]);
+    let end = datetime(&["timestamp", "end", "end_time", "ended_at", "stop"]);
+
+    let duration = [
+        (
+            &["duration_secs", "seconds", "duration"][..],
+            DurationUnit::Secs,
+        ),
+        (&["duration_mins", "minutes"][..], DurationUnit::Mins),
+    ]
+    .iter()
+    .find_map(|(names, unit)| {
+        get(names).and_then(|v| match v {
+            Value::Number(n) => n.as_f64().map(|f| unit.secs(f)),
+            Value::String(s) => parse_duration(s, *unit),
+            _ => None,
+        })
+    });
+
+    let completed = get(&["completed"]).and_then(Value::as_bool).unwrap_or(true);
+    let mut session = build_session(
+        start,
+        end,
+        duration,
+        text(&["session_type", "type", "kind"]).as_deref(),
+        text(&["task_name", "task", "description", "title"]).as_deref(),
+        text(&["note", "notes", "comment"]).as_deref(),
+        completed,
+    )?;
+
+    // Our own exports say which task a session belongs to
+    session.task_id = text(&["task_id"]).and_then(|id| id.parse().ok());
+    session.task_tags = get(&["task_tags", "tags"])
+        .and_then(Value::as_array)
+        .map(|tags| {
+            tags.iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(session)
+}
+
+/// Assemble a session; sessions are stamped with their end time
+///
+/// Rows without a usable time, or longer than `MAX_SESSION_SECS`, are skipped.
+fn build_session(
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    duration: Option<u64>,
+    session_type: Option<&str>,
+    task: Option<&str>,
+    note: Option<&str>,
+    completed: bool,
+) -> Option<Session> {
+    let duration = match (start, end, duration) {
+        (_, _, Some(duration)) => duration,
+        (Some(start), Some(end), None) => (end - start).num_seconds().try_into().ok()?,
+        _ => return None,
+    };
+    if !(1..=MAX_SESSION_SECS).contains(&duration) {
+        return None;
+    }
+    let end = match (start, end) {
+        (_, Some(end)) => end,
+        (Some(start), None) => {
+            start.checked_add_signed(chrono::Duration::seconds(duration as i64))?
+        }
+        _ => return None,
+    };
+
+    let mut session = Session::with_note(
+        session_type
+            .and_then(SessionType::parse)
+            .unwrap_or(SessionType::Work),
+        duration,
+        task.map(str::to_string),
+        note.map(str::to_string),
+    );
+    session.timestamp = end;
+    session.completed = completed;
+    Some(session)
+}
+
+/// Parse RFC 3339, "YYYY-MM-DD HH:MM[:SS]" (local time) or a Unix timestamp
+fn parse_datetime(text: &str) -> Option<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Some(time.with_timezone(&Utc));
+    }
+    if let Ok(secs) = text.parse::<i64>() {
+        return Utc.timestamp_opt(secs, 0).single();
+    }
+
+    let naive = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%m/%d/%Y %H:%M:%S",
+        "%m/%d/%Y %H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d")
+            .ok()
+            .map(|d| d.and_time(NaiveTime::MIN))
+    })?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Parse "HH:MM:SS", "MM:SS" or a plain number in the given unit
+fn parse_duration(text: &str, unit: DurationUnit) -> Option<u64> {
+    if text.contains(':') {
+        let parts: Vec<u64> = text
+            .split(':')
+            .map(|p| p.trim().parse().ok())
+            .collect::<Option<_>>()?;
+        return match parts.as_slice() {
+            [h, m, s] => h
+                .checked_mul(3600)?
+                .checked_add(m.checked_mul(60)?)?
+                .checked_add(*s),
+            [m, s] => m.checked_mul(60)?.checked_add(*s),
+            _ => None,
+        };
+    }
+    text.parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .map(|v| unit.secs(v))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::export::{write_sessions, ExportFormat};
+    use crate::persistence::sessions::Interruption;
+
+    #[test]
+    fn huge_durations_are_skipped() {
+        assert_eq!(
+            parse_duration("99999999999999999:0:0", DurationUnit::Secs),
+            None
+        );
+        assert_eq!(parse_duration("1:30:00", DurationUnit::Secs), Some(5400));
+
+        let csv = "start,duration_secs\n\
+                   2026-10-14 09:00,1500\n\
+                   2026-10-14 10:00,18446744073709551615\n\
+                   2026-10-14 11:00,90000\n";
+        let sessions = parse_csv(csv, &ColumnMapping::default()).unwrap();
+        assert_eq!(sessions.iter().filter(|s| s.is_some()).count(), 1);
+    }
+
+    #[test]
+    fn our_jsonl_export_round_trips() {
+        let mut session = Session::interrupted(
+            SessionType::Work,
+            840,
+            Some("Write report".to_string()),
+            Interruption::Reset,
+        );
+        session.profile = Some("deep-work".to_string());
+        session.project = Some("Inbox".to_string());
+        session.task_tags = vec!["work".to_string()];
+
+        let mut out = Vec::new();
+        write_sessions(ExportFormat::Jsonl, &[&session], &mut out).unwrap();
+        let imported = parse_json(&String::from_utf8(out).unwrap()).unwrap();
+
+        let imported = imported[0].as_ref().unwrap();
+        assert_eq!(
+            serde_json::to_value(imported).unwrap(),
+            serde_json::to_value(&session).unwrap()
+        );
+    }
+}
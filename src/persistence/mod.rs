@@ -1,24 +1,697 @@
+pub mod archive;
+pub mod calendar;
 pub mod config;
+pub mod export;
+pub mod import;
 pub mod sessions;
+pub mod state;
+pub mod store;
+pub mod streaks;
 pub mod tags;
 pub mod tasks;
 
-use anyhow::Result;
-use std::fs;
-use std::path::PathBuf;
+use anyhow::{bail, Context, Result};
+use chrono::Local;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use sessions::SessionType;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
-/// Get the config directory path (~/.config/pomo-tui/)
+/// How many old copies of each file are kept in `backups/`
+const MAX_BACKUPS: usize = 5;
+/// Back up a file at most this often, so quick edits don't push out older copies
+const BACKUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Get the config directory path (~/.config/pomo-tui/); it is created on the first write
 pub fn config_dir() -> Result<PathBuf> {
     let base = dirs::config_dir().ok_or_else(|| anyhow::anyhow!("Could not find config directory"))?;
-    let path = base.join("pomo-tui");
-    fs::create_dir_all(&path)?;
-    Ok(path)
+    Ok(base.join("pomo-tui"))
 }
 
-/// Get the data directory path (~/.local/share/pomo-tui/)
+/// Get the data directory path (~/.local/share/pomo-tui/); it is created on the first write
 pub fn data_dir() -> Result<PathBuf> {
     let base = dirs::data_dir().ok_or_else(|| anyhow::anyhow!("Could not find data directory"))?;
-    let path = base.join("pomo-tui");
-    fs::create_dir_all(&path)?;
-    Ok(path)
+    Ok(base.join("pomo-tui"))
+}
+
+/// Create the directory a file goes in, if it doesn't exist yet
+pub fn create_parent(path: &Path) -> Result<()> {
+    let dir = path.parent().context("file has no parent directory")?;
+    fs::create_dir_all(dir).with_context(|| format!("could not create {}", dir.display()))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Replace a file in one step: write a temporary file beside it, then rename it over
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let temp = path.with_file_name(format!(".{}.tmp", file_name(path)));
+    create_parent(path)?;
+    {
+        let mut file =
+            File::create(&temp).with_context(|| format!("could not write {}", temp.display()))?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+    }
+    fs::rename(&temp, path).with_context(|| format!("could not replace {}", path.display()))?;
+    Ok(())
+}
+
+/// Copy a file to `backups/<name>.1`, shifting older copies up to `<name>.5`
+pub fn back_up(path: &Path) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let dir = path
+        .parent()
+        .context("file has no parent directory")?
+        .join("backups");
+    fs::create_dir_all(&dir)?;
+    let name = file_name(path);
+    let backup = |n: usize| dir.join(format!("{}.{}", name, n));
+
+    let newest_age = fs::metadata(backup(1))
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+    if newest_age.is_some_and(|age| age < BACKUP_INTERVAL) {
+        return Ok(());
+    }
+
+    for n in (1..MAX_BACKUPS).rev() {
+        if backup(n).exists() {
+            fs::rename(backup(n), backup(n + 1))?;
+        }
+    }
+    fs::copy(path, backup(1))?;
+    Ok(())
+}
+
+/// Back up the previous version of a file, then replace it atomically
+pub fn save_file(path: &Path, contents: &[u8]) -> Result<()> {
+    back_up(path)?;
+    write_atomic(path, contents)
+}
+
+/// Move an unreadable file aside as `<name>.corrupt-<time>` so nothing overwrites it
+pub fn quarantine(path: &Path) -> Result<PathBuf> {
+    let moved = path.with_file_name(format!(
+        "{}.corrupt-{}",
+        file_name(path),
+        Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    fs::rename(path, &moved)?;
+    Ok(moved)
+}
+
+/// Read a JSON file, quarantining it if it doesn't parse
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("could not read {}", path.display()))?;
+    match serde_json::from_str(&contents) {
+        Ok(value) => Ok(value),
+        Err(err) => {
+            let moved = quarantine(path)?;
+            bail!(
+                "{} is damaged ({}); it was moved to {}",
+                file_name(path),
+                err,
+                moved.display()
+            )
+        }
+    }
+}
+
+/// A file written by a newer pomo-tui
+///
+/// It is left where it is: reading it would lose data, and starting empty would
+/// overwrite it, so callers should stop rather than carry on without it.
+#[derive(Debug)]
+pub struct NewerVersion {
+    pub file: String,
+    pub version: u32,
+    pub current: u32,
+}
+
+impl std::fmt::Display for NewerVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} was written by a newer pomo-tui (format {}, this version reads up to {}); update pomo-tui to use it",
+            self.file, self.version, self.current
+        )
+    }
+}
+
+impl std::error::Error for NewerVersion {}
+
+/// Persisted formats, each with its own version history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Schema {
+    Config,
+    Tasks,
+    Tags,
+    TimerState,
+    /// One record of the session log
+    Session,
+    Archive,
+}
+
+/// One upgrade step, turning a value into the next version of its schema
+type Migration = fn(&mut Value) -> Result<()>;
+
+impl Schema {
+    /// Upgrade steps in order: step `n` turns version `n + 1` into version `n + 2`
+    fn migrations(self) -> &'static [Migration] {
+        match self {
+            Schema::Session => &[
+                session_type_enum,
+                session_task_link,
+                session_shares,
+                session_mode,
+            ],
+            // Archive entries hold tasks, so the archive is upgraded along with the task list
+            Schema::Tasks | Schema::Archive => &[task_events],
+            Schema::Config | Schema::Tags | Schema::TimerState => &[],
+        }
+    }
+
+    pub fn current_version(self) -> u32 {
+        self.migrations().len() as u32 + 1
+    }
+}
+
+/// Upgrade a value written in `version` of its schema to the current version
+pub fn upgrade(schema: Schema, value: &mut Value, version: u32) -> Result<()> {
+    let current = schema.current_version();
+    if version > current {
+        bail!(
+            "written by a newer pomo-tui (format {}, this version reads up to {})",
+            version,
+            current
+        );
+    }
+    for migrate in &schema.migrations()[version.max(1) as usize - 1..] {
+        match schema {
+            Schema::Archive => migrate_archived_tasks(value, *migrate)?,
+            _ => migrate(value)?,
+        }
+    }
+    Ok(())
+}
+
+/// Run a task list migration over the task inside each archive entry
+fn migrate_archived_tasks(archive: &mut Value, migrate: Migration) -> Result<()> {
+    let Some(entries) = archive.get_mut("entries").and_then(Value::as_array_mut) else {
+        return Ok(());
+    };
+    let tasks: Vec<Value> = entries
+        .iter_mut()
+        .map(|entry| entry.get_mut("task").map(Value::take).unwrap_or_default())
+        .collect();
+    let mut store = serde_json::json!({ "tasks": tasks });
+    migrate(&mut store)?;
+
+    let Value::Array(tasks) = store["tasks"].take() else {
+        bail!("migration dropped the archived tasks");
+    };
+    for (entry, task) in entries.iter_mut().zip(tasks) {
+        entry["task"] = task;
+    }
+    Ok(())
+}
+
+/// The version recorded in a file (files from before versioning are version 1)
+pub fn version_of(value: &Value) -> u32 {
+    value.get("version").and_then(Value::as_u64).unwrap_or(1) as u32
+}
+
+/// A file's contents with its schema version written first
+#[derive(Serialize)]
+struct Versioned<'a, T> {
+    version: u32,
+    #[serde(flatten)]
+    data: &'a T,
+}
+
+/// Pretty JSON for a file in the current version of its schema
+pub fn to_versioned_json<T: Serialize>(schema: Schema, data: &T) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&Versioned {
+        version: schema.current_version(),
+        data,
+    })?)
+}
+
+/// Save a file in the current version of its schema, keeping a backup of the old one
+pub fn save_versioned<T: Serialize>(path: &Path, schema: Schema, data: &T) -> Result<()> {
+    save_file(path, to_versioned_json(schema, data)?.as_bytes())
+}
+
+/// Refuse a file written by a newer version of its schema
+fn check_not_newer(path: &Path, schema: Schema, version: u32) -> Result<()> {
+    let current = schema.current_version();
+    if version > current {
+        return Err(NewerVersion {
+            file: path.display().to_string(),
+            version,
+            current,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Read a versioned file, upgrading it step by step to the current format
+///
+/// An upgraded file is written back (the original stays in `backups/`). A file
+/// that can't be parsed or upgraded is quarantined, except one from a newer
+/// version, which is left alone (see `NewerVersion`).
+pub fn read_versioned<T: Serialize + DeserializeOwned>(path: &Path, schema: Schema) -> Result<T> {
+    let mut value: Value = read_json(path)?;
+    let version = version_of(&value);
+    check_not_newer(path, schema, version)?;
+
+    let upgraded =
+        upgrade(schema, &mut value, version).and_then(|()| Ok(serde_json::from_value::<T>(value)?));
+    match upgraded {
+        Ok(data) => {
+            if version < schema.current_version() {
+                save_versioned(path, schema, &data)?;
+            }
+            Ok(data)
+        }
+        Err(err) => {
+            let moved = quarantine(path)?;
+            bail!(
+                "{} could not be read ({:#}); it was moved to {}",
+                file_name(path),
+                err,
+                moved.display()
+            )
+        }
+    }
+}
+
+/// Read a versioned file the way `read_versioned` does, but never write to disk
+///
+/// An older file is upgraded in memory only, and a damaged one is an error that
+/// leaves the file where it is.
+pub fn peek_versioned<T: DeserializeOwned>(path: &Path, schema: Schema) -> Result<T> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("could not read {}", path.display()))?;
+    let mut value: Value = serde_json::from_str(&contents)
+        .with_context(|| format!("{} is damaged", file_name(path)))?;
+    let version = version_of(&value);
+    check_not_newer(path, schema, version)?;
+    upgrade(schema, &mut value, version)
+        .and_then(|()| Ok(serde_json::from_value(value)?))
+        .with_context(|| format!("{} could not be read", file_name(path)))
+}
+
+// Migrations
+
+/// Session v1 → v2: free-form `session_type` strings become `SessionType` names,
+/// and optional fields are written out explicitly
+///
+/// A type we don't know is an error rather than a guess, so the record is set
+/// aside with the damaged ones instead of counting as focus time.
+fn session_type_enum(session: &mut Value) -> Result<()> {
+    let record = session
+        .as_object_mut()
+        .context("session record is not an object")?;
+
+    let raw = record
+        .get("session_type")
+        .and_then(Value::as_str)
+        .unwrap_or("work")
+        .to_string();
+    let session_type =
+        SessionType::parse(&raw).with_context(|| format!("unknown session type: {}", raw))?;
+    record.insert("session_type".into(), session_type.as_str().into());
+
+    record.entry("completed").or_insert(true.into());
+    for field in ["task_name", "note", "interruption", "profile"] {
+        record.entry(field).or_insert(Value::Null);
+    }
+    Ok(())
+}
+
+/// Session v2 → v3: sessions carry the id and tags of their task
+///
+/// The task can't be looked up here; `SessionHistory::link_tasks` matches old
+/// sessions to tasks by name once the task list is loaded.
+fn session_task_link(session: &mut Value) -> Result<()> {
+    let record = session
+        .as_object_mut()
+        .context("session record is not an object")?;
+    record.entry("task_id").or_insert(Value::Null);
+    record
+        .entry("task_tags")
+        .or_insert(Value::Array(Vec::new()));
+    Ok(())
+}
+
+/// Session v3 → v4: sessions split between tasks record each task's share
+fn session_shares(session: &mut Value) -> Result<()> {
+    let record = session
+        .as_object_mut()
+        .context("session record is not an object")?;
+    record.entry("shares").or_insert(Value::Array(Vec::new()));
+    Ok(())
+}
+
+/// Session v4 → v5: sessions say which timer recorded them
+///
+/// Stopwatch and custom-timer sessions weren't marked before, so older sessions
+/// are all taken to be pomodoros.
+fn session_mode(session: &mut Value) -> Result<()> {
+    let record = session
+        .as_object_mut()
+        .context("session record is not an object")?;
+    record.entry("mode").or_insert("pomodoro".into());
+    Ok(())
+}
+
+/// Tasks v1 → v2: start each task's event log from what the task already records
+///
+/// Before v2, `created_at` was rewritten on every save, so it is only as good as
+/// the last time the file was saved; it is kept as the creation time regardless.
+fn task_events(store: &mut Value) -> Result<()> {
+    let Some(tasks) = store.get_mut("tasks").and_then(Value::as_array_mut) else {
+        return Ok(());
+    };
+    for task in tasks {
+        let task = task.as_object_mut().context("task is not an object")?;
+        if task.get("events").is_some_and(|events| !events.is_null()) {
+            continue;
+        }
+        let created_at = task.get("created_at").cloned().unwrap_or(Value::Null);
+        let mut events = vec![serde_json::json!({ "at": created_at, "kind": "created" })];
+        if let Some(completed_at) = task.get("completed_at").filter(|at| !at.is_null()) {
+            events.push(serde_json::json!({ "at": completed_at, "kind": "completed" }));
+        }
+        task.insert("events".into(), events.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::archive::ArchiveStore;
+    use super::config::Config;
+    use super::sessions::{Interruption, Session, SessionType};
+    use super::state::TimerSnapshot;
+    use super::store::{JsonFileStore, JsonlStore, SessionStore};
+    use super::tags::TagStore;
+    use super::tasks::{TaskEvent, TaskEventKind, TaskStore};
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    /// Copy a fixture into a fresh directory, since loading may rewrite it
+    fn fixture(name: &str) -> (TempDir, PathBuf) {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(Path::new(name).file_name().unwrap());
+        let source = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name);
+        fs::copy(source, &path).unwrap();
+        (dir, path)
+    }
+
+    /// Version of a JSON file, or of a JSONL log from its header line
+    fn file_version(path: &Path) -> u32 {
+        let contents = fs::read_to_string(path).unwrap();
+        let json = serde_json::from_str(&contents)
+            .or_else(|_| serde_json::from_str(contents.lines().next().unwrap_or_default()))
+            .unwrap();
+        version_of(&json)
+    }
+
+    fn types(sessions: &[Session]) -> Vec<SessionType> {
+        sessions.iter().map(|s| s.session_type).collect()
+    }
+
+    #[test]
+    fn legacy_sessions_json_keeps_every_session() {
+        let (_dir, path) = fixture("v1/sessions.json");
+        let loaded = JsonFileStore::new(path).load().unwrap();
+
+        assert_eq!(loaded.sessions.len(), 4);
+        assert_eq!(
+            types(&loaded.sessions),
+            [
+                SessionType::Work,
+                SessionType::ShortBreak,
+                SessionType::Work,
+                SessionType::LongBreak
+            ]
+        );
+        let interrupted = &loaded.sessions[2];
+        assert!(!interrupted.completed);
+        assert_eq!(interrupted.duration_secs, 840);
+        assert_eq!(interrupted.note.as_deref(), Some("phone call"));
+        assert_eq!(interrupted.interruption, Some(Interruption::Reset));
+        assert_eq!(loaded.sessions[3].profile.as_deref(), Some("classic"));
+    }
+
+    #[test]
+    fn legacy_sessions_json_migrates_to_a_versioned_log() {
+        let (dir, path) = fixture("v1/sessions.json");
+        let legacy = JsonFileStore::new(path);
+        let mut log = JsonlStore::new(dir.path().join("sessions.jsonl"));
+
+        assert_eq!(store::migrate(&legacy, &mut log).unwrap(), 4);
+        let header = fs::read_to_string(dir.path().join("sessions.jsonl")).unwrap();
+        assert!(header.starts_with(&format!(
+            "{{\"version\":{}}}\n",
+            Schema::Session.current_version()
+        )));
+
+        let before = legacy.load().unwrap().sessions;
+        let after = log.load().unwrap().sessions;
+        assert_eq!(
+            serde_json::to_value(&before).unwrap(),
+            serde_json::to_value(&after).unwrap()
+        );
+    }
+
+    #[test]
+    fn unversioned_jsonl_is_upgraded_in_place() {
+        let (dir, path) = fixture("v1/sessions.jsonl");
+        let store = JsonlStore::new(path.clone());
+
+        let loaded = store.load().unwrap();
+        assert_eq!(
+            types(&loaded.sessions),
+            [
+                SessionType::Work,
+                SessionType::ShortBreak,
+                SessionType::LongBreak
+            ]
+        );
+
+        // The log is rewritten with a header and the original is backed up
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 4);
+        assert_eq!(file_version(&path), Schema::Session.current_version());
+        assert!(dir.path().join("backups/sessions.jsonl.1").exists());
+
+        // Reading the upgraded log gives the same sessions back
+        let again = store.load().unwrap().sessions;
+        assert_eq!(
+            serde_json::to_value(&loaded.sessions).unwrap(),
+            serde_json::to_value(&again).unwrap()
+        );
+    }
+
+    #[test]
+    fn unknown_session_types_are_set_aside() {
+        let (dir, path) = fixture("v1/sessions.jsonl");
+        let loaded = JsonlStore::new(path).load().unwrap();
+
+        assert_eq!(loaded.sessions.len(), 3);
+        assert!(loaded.warning.unwrap().contains("1 damaged session record"));
+        let aside = fs::read_dir(dir.path())
+            .unwrap()
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .find(|path| file_name(path).starts_with("sessions.jsonl.corrupt-"))
+            .unwrap();
+        assert!(fs::read_to_string(aside).unwrap().contains("meditation"));
+    }
+
+    #[test]
+    fn peeking_never_writes() {
+        let (dir, log) = fixture("v1/sessions.jsonl");
+        fs::write(&log, fs::read_to_string(&log).unwrap() + "{not json\n").unwrap();
+        let (_tasks_dir, tasks) = fixture("v1/tasks.json");
+        let before = (fs::read(&log).unwrap(), fs::read(&tasks).unwrap());
+
+        let peeked = JsonlStore::new(log.clone()).peek().unwrap();
+        assert_eq!(peeked.sessions.len(), 3);
+        assert!(peeked
+            .warning
+            .unwrap()
+            .contains("2 damaged session record(s)"));
+        let store: TaskStore = peek_versioned(&tasks, Schema::Tasks).unwrap();
+        assert!(store.tasks.iter().all(|t| !t.events.is_empty()));
+
+        // Still version 1, with the damaged line, and nothing beside it
+        assert_eq!((fs::read(&log).unwrap(), fs::read(&tasks).unwrap()), before);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn v1_config_keeps_its_values() {
+        let (_dir, path) = fixture("v1/config.json");
+        let config: Config = read_versioned(&path, Schema::Config).unwrap();
+
+        assert_eq!(config.work_duration_mins, 50);
+        assert_eq!(config.short_break_mins, 10);
+        assert_eq!(config.long_break_mins, 30);
+        assert_eq!(config.sessions_before_long_break, 3);
+        assert_eq!(config.daily_goal_pomodoros, 6);
+        assert_eq!(config.theme, "nord");
+        assert!(config.auto_start_breaks);
+        assert!(!config.notifications_enabled);
+        // Fields added since fall back to their defaults
+        assert_eq!(
+            config.flow_break_divisor,
+            Config::default().flow_break_divisor
+        );
+    }
+
+    #[test]
+    fn v1_tasks_tags_and_state_load() {
+        let (_dir, path) = fixture("v1/tasks.json");
+        let tasks: TaskStore = read_versioned(&path, Schema::Tasks).unwrap();
+        let names: Vec<&str> = tasks.tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["Write report", "Buy milk"]);
+        assert_eq!(tasks.tasks[0].pomodoros_spent, 3);
+        assert_eq!(tasks.tasks[0].tags, ["work"]);
+        assert!(tasks.tasks[1].completed);
+        let created = "2026-10-01T08:00:00Z".parse().unwrap();
+        assert_eq!(tasks.tasks[0].created_at, created);
+        assert_eq!(
+            tasks.tasks[0].events,
+            [TaskEvent {
+                at: created,
+                kind: TaskEventKind::Created
+            }]
+        );
+
+        let (_dir, path) = fixture("v1/tags.json");
+        let tags: TagStore = read_versioned(&path, Schema::Tags).unwrap();
+        assert_eq!(tags.tags.len(), 2);
+        assert_eq!(tags.tags[0].count, 12);
+
+        let (_dir, path) = fixture("v1/state.json");
+        let snapshot: TimerSnapshot = read_versioned(&path, Schema::TimerState).unwrap();
+        assert_eq!(snapshot.timer_state, "work");
+        assert_eq!(snapshot.remaining_secs, 780);
+        assert!(snapshot.is_running);
+    }
+
+    #[test]
+    fn saved_files_carry_their_version() {
+        let dir = TempDir::new().unwrap();
+        for (schema, name) in [
+            (Schema::Config, "config.json"),
+            (Schema::Tasks, "tasks.json"),
+        ] {
+            let path = dir.path().join(name);
+            match schema {
+                Schema::Config => save_versioned(&path, schema, &Config::default()).unwrap(),
+                _ => save_versioned(&path, schema, &TaskStore::default()).unwrap(),
+            }
+            assert_eq!(file_version(&path), schema.current_version());
+        }
+
+        let config: Config =
+            read_versioned(&dir.path().join("config.json"), Schema::Config).unwrap();
+        assert_eq!(
+            serde_json::to_value(config).unwrap(),
+            serde_json::to_value(Config::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn files_from_a_newer_version_are_left_alone() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("tasks.json");
+        let contents = r#"{ "version": 99, "tasks": [] }"#;
+        fs::write(&path, contents).unwrap();
+
+        let error = read_versioned::<TaskStore>(&path, Schema::Tasks).unwrap_err();
+        assert!(error.is::<NewerVersion>());
+        assert!(format!("{:#}", error).contains("newer pomo-tui"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let log = dir.path().join("sessions.jsonl");
+        fs::write(&log, "{\"version\":99}\n").unwrap();
+        assert!(JsonlStore::new(log.clone())
+            .load()
+            .unwrap_err()
+            .is::<NewerVersion>());
+        assert_eq!(fs::read_to_string(&log).unwrap(), "{\"version\":99}\n");
+    }
+
+    #[test]
+    fn archived_tasks_are_upgraded_with_the_task_list() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("archive.json");
+        let v1_task = serde_json::json!({
+            "id": "5d4c3b2a-1f0e-4d9c-8b7a-6f5e4d3c2b10",
+            "name": "Old report",
+            "completed": true,
+            "pomodoros_spent": 2,
+            "tags": [],
+            "created_at": "2026-09-01T09:00:00Z",
+            "completed_at": "2026-09-03T17:00:00Z",
+        });
+        let archive = serde_json::json!({
+            "entries": [{ "task": v1_task, "archived_at": "2026-09-04T08:00:00Z", "reason": "cleared" }],
+        });
+        fs::write(&path, archive.to_string()).unwrap();
+
+        let archive: ArchiveStore = read_versioned(&path, Schema::Archive).unwrap();
+        let kinds: Vec<TaskEventKind> = archive.entries[0]
+            .task
+            .events
+            .iter()
+            .map(|e| e.kind)
+            .collect();
+        assert_eq!(kinds, [TaskEventKind::Created, TaskEventKind::Completed]);
+        assert_eq!(file_version(&path), Schema::Tasks.current_version());
+    }
+
+    #[test]
+    fn every_migration_step_is_reachable() {
+        for schema in [
+            Schema::Config,
+            Schema::Tasks,
+            Schema::Tags,
+            Schema::TimerState,
+            Schema::Session,
+            Schema::Archive,
+        ] {
+            assert_eq!(
+                schema.migrations().len() as u32,
+                schema.current_version() - 1
+            );
+            let mut value = serde_json::json!({});
+            if schema == Schema::Session {
+                value = serde_json::json!({ "session_type": "work" });
+            }
+            upgrade(schema, &mut value, 1).unwrap();
+        }
+    }
 }
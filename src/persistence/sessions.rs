@@ -1,170 +1,425 @@
+use super::calendar::DayBoundary;
+use super::config::Config;
+use super::store;
+use super::streaks::StreakRules;
+use super::tags;
+use super::tasks::TaskData;
 use anyhow::Result;
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 
-/// A completed Pomodoro session
+/// Why a session ended before its timer ran out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Interruption {
+    Reset,
+    Skipped,
+    Abandoned,
+}
+
+impl Interruption {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Interruption::Reset => "reset",
+            Interruption::Skipped => "skipped",
+            Interruption::Abandoned => "abandoned",
+        }
+    }
+}
+
+/// What kind of phase a session was
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionType {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+impl SessionType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionType::Work => "work",
+            SessionType::ShortBreak => "short_break",
+            SessionType::LongBreak => "long_break",
+        }
+    }
+
+    /// Parse our own names and the ones other tools use ("Short Break", "focus", ...)
+    pub fn parse(name: &str) -> Option<Self> {
+        match name
+            .trim()
+            .to_ascii_lowercase()
+            .replace([' ', '-'], "_")
+            .as_str()
+        {
+            "work" | "focus" | "pomodoro" => Some(SessionType::Work),
+            "short_break" | "shortbreak" | "break" | "rest" => Some(SessionType::ShortBreak),
+            "long_break" | "longbreak" => Some(SessionType::LongBreak),
+            _ => None,
+        }
+    }
+}
+
+/// Which timer recorded a session; only Pomodoro-mode work earns pomodoros
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionMode {
+    #[default]
+    Pomodoro,
+    /// A custom-length countdown
+    Timer,
+    Stopwatch,
+}
+
+/// Part of a session spent on one task (or one of its subtasks)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskShare {
+    pub task_id: Uuid,
+    #[serde(default)]
+    pub subtask_id: Option<Uuid>,
+    pub task_name: String,
+    #[serde(default)]
+    pub task_tags: Vec<String>,
+    pub secs: u64,
+}
+
+/// Tidy up how a session's time was split: drop shares shorter than `min_secs`, put
+/// the largest first, and give it whatever the others don't cover so the shares add
+/// up to `total_secs`
+pub fn settle_shares(shares: &mut Vec<TaskShare>, total_secs: u64, min_secs: u64) {
+    shares.retain(|share| share.secs >= min_secs);
+    shares.sort_by_key(|share| std::cmp::Reverse(share.secs));
+    let rest: u64 = shares.iter().skip(1).map(|share| share.secs).sum();
+    if let Some(largest) = shares.first_mut() {
+        largest.secs = total_secs.saturating_sub(rest);
+    }
+}
+
+/// A recorded Pomodoro session (completed, or cut short)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Session {
     pub id: Uuid,
     pub timestamp: DateTime<Utc>,
-    pub session_type: String,
+    pub session_type: SessionType,
     pub duration_secs: u64,
     pub completed: bool,
+    /// The task's name when the session was recorded
     pub task_name: Option<String>,
+    /// The task the session was credited to
+    #[serde(default)]
+    pub task_id: Option<Uuid>,
+    /// The task's tags when the session was recorded
+    #[serde(default)]
+    pub task_tags: Vec<String>,
+    /// How the time was split when more than one task was worked on; the session
+    /// itself is credited to one of them
+    #[serde(default)]
+    pub shares: Vec<TaskShare>,
     #[serde(default)]
     pub note: Option<String>,
+    #[serde(default)]
+    pub interruption: Option<Interruption>,
+    /// Timer profile active when the session was recorded
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Task list the session was recorded under
+    #[serde(default)]
+    pub project: Option<String>,
+    #[serde(default)]
+    pub mode: SessionMode,
 }
 
 impl Session {
-    pub fn new(session_type: &str, duration_secs: u64, task_name: Option<String>) -> Self {
+    pub fn new(session_type: SessionType, duration_secs: u64, task_name: Option<String>) -> Self {
         Self {
             id: Uuid::new_v4(),
             timestamp: Utc::now(),
-            session_type: session_type.to_string(),
+            session_type,
             duration_secs,
             completed: true,
             task_name,
+            task_id: None,
+            task_tags: Vec::new(),
+            shares: Vec::new(),
             note: None,
+            interruption: None,
+            profile: None,
+            project: None,
+            mode: SessionMode::Pomodoro,
         }
     }
 
-    pub fn with_note(session_type: &str, duration_secs: u64, task_name: Option<String>, note: Option<String>) -> Self {
+    pub fn with_note(
+        session_type: SessionType,
+        duration_secs: u64,
+        task_name: Option<String>,
+        note: Option<String>,
+    ) -> Self {
         Self {
             id: Uuid::new_v4(),
             timestamp: Utc::now(),
-            session_type: session_type.to_string(),
+            session_type,
             duration_secs,
             completed: true,
             task_name,
+            task_id: None,
+            task_tags: Vec::new(),
+            shares: Vec::new(),
             note,
+            interruption: None,
+            profile: None,
+            project: None,
+            mode: SessionMode::Pomodoro,
         }
     }
-}
 
-/// Session history storage with streak tracking
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SessionHistory {
-    pub sessions: Vec<Session>,
-    pub current_streak: u32,
-    pub longest_streak: u32,
-    pub last_session_date: Option<NaiveDate>,
-}
-
-impl Default for SessionHistory {
-    fn default() -> Self {
+    /// A session that ended early, with the focus time actually spent
+    pub fn interrupted(
+        session_type: SessionType,
+        elapsed_secs: u64,
+        task_name: Option<String>,
+        reason: Interruption,
+    ) -> Self {
         Self {
-            sessions: Vec::new(),
-            current_streak: 0,
-            longest_streak: 0,
-            last_session_date: None,
+            id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            session_type,
+            duration_secs: elapsed_secs,
+            completed: false,
+            task_name,
+            task_id: None,
+            task_tags: Vec::new(),
+            shares: Vec::new(),
+            note: None,
+            interruption: Some(reason),
+            profile: None,
+            project: None,
+            mode: SessionMode::Pomodoro,
         }
     }
+
+    /// A finished Pomodoro-mode work session; stopwatch and custom-timer focus time doesn't count
+    pub fn earns_pomodoro(&self) -> bool {
+        self.session_type == SessionType::Work
+            && self.completed
+            && self.mode == SessionMode::Pomodoro
+    }
+
+    /// Credit the session to a task, keeping a copy of its name and tags
+    pub fn link_task(&mut self, id: Uuid, name: &str, tags: &[String]) {
+        self.task_id = Some(id);
+        self.task_name = Some(name.to_string());
+        self.task_tags = tags.to_vec();
+    }
+}
+
+/// Sessions not yet written to the store
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+enum Unsaved {
+    #[default]
+    Nothing,
+    /// Sessions from this index on are new
+    From(usize),
+    /// The log was reordered or cleared and has to be rewritten
+    Everything,
+}
+
+/// Session history, indexed by day; streaks are derived from the sessions themselves
+#[derive(Debug, Clone, Default)]
+pub struct SessionHistory {
+    sessions: Vec<Session>,
+    unsaved: Unsaved,
+    /// Day boundaries for goals, streaks and charts (from the config)
+    day: DayBoundary,
+    streak_rules: StreakRules,
+    /// Indices into `sessions` for each day
+    by_day: BTreeMap<NaiveDate, Vec<usize>>,
+    /// Completed pomodoros per day, kept in step with `sessions`
+    daily_pomodoros: BTreeMap<NaiveDate, u32>,
+    /// Set when part of the log was damaged and had to be set aside on load
+    pub load_warning: Option<String>,
 }
 
 impl SessionHistory {
-    fn file_path() -> Result<PathBuf> {
-        Ok(super::data_dir()?.join("sessions.json"))
+    pub fn load() -> Result<Self> {
+        Ok(Self::from_loaded(store::open()?.load()?))
     }
 
-    pub fn load() -> Result<Self> {
-        let path = Self::file_path()?;
+    /// Read the history without migrating or repairing the log (see `store::peek`)
+    pub fn peek() -> Result<Self> {
+        Ok(Self::from_loaded(store::peek()?))
+    }
 
-        if path.exists() {
-            let contents = fs::read_to_string(&path)?;
-            let mut history: SessionHistory = serde_json::from_str(&contents).unwrap_or_default();
-            history.recalculate_streak();
-            Ok(history)
-        } else {
-            let history = SessionHistory::default();
-            history.save()?;
-            Ok(history)
-        }
+    fn from_loaded(loaded: store::Loaded) -> Self {
+        let mut history = SessionHistory {
+            sessions: loaded.sessions,
+            load_warning: loaded.warning,
+            ..Self::default()
+        };
+        history.reindex();
+        history
     }
 
-    pub fn save(&self) -> Result<()> {
-        let path = Self::file_path()?;
-        let contents = serde_json::to_string_pretty(self)?;
-        fs::write(path, contents)?;
+    /// Write new sessions to the store (or the whole log if it was rewritten)
+    pub fn save(&mut self) -> Result<()> {
+        match self.unsaved {
+            Unsaved::Nothing => return Ok(()),
+            Unsaved::From(index) => store::open()?.append(&self.sessions[index..])?,
+            Unsaved::Everything => store::open()?.rewrite(&self.sessions)?,
+        }
+        self.unsaved = Unsaved::Nothing;
         Ok(())
     }
 
-    /// Add a new session and update streak
+    /// All sessions, oldest first
+    pub fn sessions(&self) -> &[Session] {
+        &self.sessions
+    }
+
+    /// Count days and streaks the way the config says
+    pub fn use_config(&mut self, config: &Config) {
+        self.day = DayBoundary::from_config(config);
+        self.streak_rules = StreakRules::from_config(config);
+        self.reindex();
+    }
+
+    /// Add a new session
     pub fn add(&mut self, session: Session) {
-        let session_date = session.timestamp.date_naive();
-        
-        // Only update streak for work sessions
-        if session.session_type == "work" {
-            self.update_streak(session_date);
+        if self.unsaved == Unsaved::Nothing {
+            self.unsaved = Unsaved::From(self.sessions.len());
         }
-        
+        self.index(self.sessions.len(), &session);
         self.sessions.push(session);
     }
 
-    /// Update streak based on new session date
-    fn update_streak(&mut self, session_date: NaiveDate) {
-        let _today = Utc::now().date_naive();
-        
-        match self.last_session_date {
-            Some(last_date) => {
-                let days_diff = (session_date - last_date).num_days();
-                
-                if days_diff == 0 {
-                    // Same day, streak continues
-                } else if days_diff == 1 {
-                    // Consecutive day, increment streak
-                    self.current_streak += 1;
-                    if self.current_streak > self.longest_streak {
-                        self.longest_streak = self.current_streak;
-                    }
-                } else {
-                    // Streak broken, reset
-                    self.current_streak = 1;
+    /// Add sessions from elsewhere (e.g. an import), keeping the log in time order
+    pub fn merge(&mut self, sessions: Vec<Session>) {
+        self.sessions.extend(sessions);
+        self.sessions.sort_by_key(|s| s.timestamp);
+        self.unsaved = Unsaved::Everything;
+        self.reindex();
+    }
+
+    /// Link sessions that only name their task (older or imported ones) to the task by that name
+    ///
+    /// Names are compared ignoring case; a name shared by several tasks is left alone.
+    /// Returns how many sessions were linked.
+    pub fn link_tasks(&mut self, tasks: &[TaskData]) -> usize {
+        let mut by_name: HashMap<String, Option<&TaskData>> = HashMap::new();
+        for task in tasks {
+            by_name
+                .entry(task.name.trim().to_lowercase())
+                .and_modify(|found| *found = None)
+                .or_insert(Some(task));
+        }
+
+        let mut linked = 0;
+        for session in self.sessions.iter_mut().filter(|s| s.task_id.is_none()) {
+            let Some(name) = &session.task_name else {
+                continue;
+            };
+            if let Some(Some(task)) = by_name.get(&name.trim().to_lowercase()) {
+                session.task_id = Some(task.id);
+                if session.task_tags.is_empty() {
+                    session.task_tags = task.tags.clone();
                 }
+                linked += 1;
             }
-            None => {
-                // First session ever
-                self.current_streak = 1;
-                self.longest_streak = 1;
-            }
         }
-        
-        self.last_session_date = Some(session_date);
+        if linked > 0 {
+            self.unsaved = Unsaved::Everything;
+        }
+        linked
     }
 
-    /// Recalculate streak on load (in case app wasn't used yesterday)
-    fn recalculate_streak(&mut self) {
-        let today = Utc::now().date_naive();
-        
-        if let Some(last_date) = self.last_session_date {
-            let days_diff = (today - last_date).num_days();
-            
-            if days_diff > 1 {
-                // Streak broken - missed more than one day
-                self.current_streak = 0;
+    /// Rename a tag in every session's task tags (see `tags::rename_in`); returns how many changed
+    pub fn rename_tag(&mut self, from: &str, to: &str) -> usize {
+        let mut changed = 0;
+        for session in &mut self.sessions {
+            let mut any = tags::rename_in(&mut session.task_tags, from, to);
+            for share in &mut session.shares {
+                any |= tags::rename_in(&mut share.task_tags, from, to);
             }
-            // If days_diff is 0 or 1, streak is still valid
+            changed += any as usize;
+        }
+        if changed > 0 {
+            self.unsaved = Unsaved::Everything;
         }
+        changed
+    }
+
+    /// Forget every session
+    pub fn clear(&mut self) {
+        self.sessions.clear();
+        self.unsaved = Unsaved::Everything;
+        self.reindex();
+    }
+
+    fn index(&mut self, position: usize, session: &Session) {
+        let date = self.day.day_of(session.timestamp);
+        self.by_day.entry(date).or_default().push(position);
+        if session.earns_pomodoro() {
+            *self.daily_pomodoros.entry(date).or_default() += 1;
+        }
+    }
+
+    fn reindex(&mut self) {
+        self.by_day.clear();
+        self.daily_pomodoros.clear();
+        let sessions = std::mem::take(&mut self.sessions);
+        for (position, session) in sessions.iter().enumerate() {
+            self.index(position, session);
+        }
+        self.sessions = sessions;
+    }
+
+    /// Sessions on the days from `from` to `to`, inclusive
+    pub fn between(&self, from: NaiveDate, to: NaiveDate) -> impl Iterator<Item = &Session> {
+        self.by_day
+            .range(from..=to)
+            .flat_map(|(_, positions)| positions.iter().map(|&i| &self.sessions[i]))
+    }
+
+    /// Focus time on the days from `from` to `to`, inclusive
+    fn focus_secs_between(&self, from: NaiveDate, to: NaiveDate) -> u64 {
+        self.between(from, to)
+            .filter(|s| s.session_type == SessionType::Work)
+            .map(|s| s.duration_secs)
+            .sum()
+    }
+
+    /// Consecutive streak days up to today
+    pub fn current_streak(&self) -> u32 {
+        self.streak_rules
+            .streaks(&self.daily_pomodoros, self.day.today())
+            .0
+    }
+
+    pub fn longest_streak(&self) -> u32 {
+        self.streak_rules
+            .streaks(&self.daily_pomodoros, self.day.today())
+            .1
+    }
+
+    /// Whether today is a freeze day (it can't break the streak)
+    pub fn is_freeze_day(&self) -> bool {
+        self.streak_rules.is_frozen(self.day.today())
     }
 
     /// Get today's completed work sessions count
     pub fn today_pomodoro_count(&self) -> usize {
-        let today = Utc::now().date_naive();
-        self.sessions
-            .iter()
-            .filter(|s| s.timestamp.date_naive() == today && s.session_type == "work")
-            .count()
+        self.daily_pomodoros
+            .get(&self.day.today())
+            .copied()
+            .unwrap_or(0) as usize
     }
 
     pub fn today_focus_secs(&self) -> u64 {
-        let today = Utc::now().date_naive();
-        self.sessions
-            .iter()
-            .filter(|s| s.timestamp.date_naive() == today && s.session_type == "work")
-            .map(|s| s.duration_secs)
-            .sum()
+        let today = self.day.today();
+        self.focus_secs_between(today, today)
     }
 
     pub fn today_session_count(&self) -> usize {
@@ -172,29 +427,20 @@ impl SessionHistory {
     }
 
     pub fn week_focus_secs(&self) -> u64 {
-        use chrono::Datelike;
-        let now = Utc::now();
-        let week_start = now.date_naive()
-            - chrono::Duration::days(now.weekday().num_days_from_monday() as i64);
-
-        self.sessions
-            .iter()
-            .filter(|s| s.timestamp.date_naive() >= week_start && s.session_type == "work")
-            .map(|s| s.duration_secs)
-            .sum()
+        self.focus_secs_between(self.day.week_start(), self.day.today())
     }
 
     pub fn total_focus_secs(&self) -> u64 {
         self.sessions
             .iter()
-            .filter(|s| s.session_type == "work")
+            .filter(|s| s.session_type == SessionType::Work)
             .map(|s| s.duration_secs)
             .sum()
     }
 
     pub fn last_7_days_focus(&self) -> Vec<(String, u64)> {
         use chrono::Datelike;
-        let today = Utc::now().date_naive();
+        let today = self.day.today();
         let days = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
 
         (0..7)
@@ -202,18 +448,373 @@ impl SessionHistory {
             .map(|i| {
                 let date = today - chrono::Duration::days(i as i64);
                 let day_name = days[date.weekday().num_days_from_monday() as usize];
-                let secs: u64 = self
-                    .sessions
-                    .iter()
-                    .filter(|s| s.timestamp.date_naive() == date && s.session_type == "work")
-                    .map(|s| s.duration_secs)
-                    .sum();
-                (day_name.to_string(), secs)
+                (day_name.to_string(), self.focus_secs_between(date, date))
             })
             .collect()
     }
 
+    /// Share of work sessions that ran to completion (None before the first one)
+    pub fn completion_rate(&self) -> Option<f64> {
+        let (completed, total) = self
+            .sessions
+            .iter()
+            .filter(|s| s.session_type == SessionType::Work)
+            .fold((0usize, 0usize), |(done, total), s| {
+                (done + s.completed as usize, total + 1)
+            });
+
+        if total == 0 {
+            None
+        } else {
+            Some(completed as f64 / total as f64)
+        }
+    }
+
+    /// History made of one profile's sessions, with streaks recomputed
+    pub fn for_profile(&self, profile: &str) -> Self {
+        let mut history = Self {
+            day: self.day,
+            streak_rules: self.streak_rules.clone(),
+            ..Self::default()
+        };
+        for session in self
+            .sessions
+            .iter()
+            .filter(|s| s.profile.as_deref() == Some(profile))
+        {
+            history.add(session.clone());
+        }
+        history
+    }
+
+    /// Focus time per project on the days from `from` to `to`, largest first
+    ///
+    /// Sessions recorded before projects existed are grouped under `None`.
+    pub fn focus_by_project(&self, from: NaiveDate, to: NaiveDate) -> Vec<(Option<String>, u64)> {
+        let mut totals: BTreeMap<Option<String>, u64> = BTreeMap::new();
+        for session in self
+            .between(from, to)
+            .filter(|s| s.session_type == SessionType::Work)
+        {
+            *totals.entry(session.project.clone()).or_default() += session.duration_secs;
+        }
+        let mut totals: Vec<_> = totals.into_iter().collect();
+        totals.sort_by_key(|(_, secs)| std::cmp::Reverse(*secs));
+        totals
+    }
+
+    /// Focus time per project this week
+    pub fn week_focus_by_project(&self) -> Vec<(Option<String>, u64)> {
+        self.focus_by_project(self.day.week_start(), self.day.today())
+    }
+
+    /// Focus time per task on the days from `from` to `to`, largest first
+    ///
+    /// Split sessions count each task's share of the time, and time on a subtask counts
+    /// toward its task. Each entry has the task's id (None for sessions never linked to a
+    /// task) and the name it had in its latest session.
+    pub fn focus_by_task(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Vec<(Option<Uuid>, String, u64)> {
+        let mut totals: HashMap<(Option<Uuid>, String), (String, u64)> = HashMap::new();
+        let mut add = |id: Option<Uuid>, name: &str, secs: u64| {
+            // Unlinked sessions can only be told apart by name
+            let key = match id {
+                Some(id) => (Some(id), String::new()),
+                None => (None, name.to_lowercase()),
+            };
+            let total = totals.entry(key).or_default();
+            total.0 = name.to_string();
+            total.1 += secs;
+        };
+        for session in self
+            .between(from, to)
+            .filter(|s| s.session_type == SessionType::Work)
+        {
+            if !session.shares.is_empty() {
+                for share in &session.shares {
+                    add(Some(share.task_id), &share.task_name, share.secs);
+                }
+            } else if let Some(name) = &session.task_name {
+                add(session.task_id, name, session.duration_secs);
+            }
+        }
+        let mut totals: Vec<_> = totals
+            .into_iter()
+            .map(|((id, _), (name, secs))| (id, name, secs))
+            .collect();
+        totals.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.1.cmp(&b.1)));
+        totals
+    }
+
+    /// Focus time per task this week
+    pub fn week_focus_by_task(&self) -> Vec<(Option<Uuid>, String, u64)> {
+        self.focus_by_task(self.day.week_start(), self.day.today())
+    }
+
+    /// Focus time per tag on the days from `from` to `to`, largest first
+    ///
+    /// Uses the tags a task had when each session was recorded; a session with
+    /// several tags counts toward each of them, and split sessions count each share.
+    pub fn focus_by_tag(&self, from: NaiveDate, to: NaiveDate) -> Vec<(String, u64)> {
+        let mut totals: BTreeMap<String, u64> = BTreeMap::new();
+        for session in self
+            .between(from, to)
+            .filter(|s| s.session_type == SessionType::Work)
+        {
+            let parts: Vec<(&[String], u64)> = if session.shares.is_empty() {
+                vec![(&session.task_tags, session.duration_secs)]
+            } else {
+                session
+                    .shares
+                    .iter()
+                    .map(|share| (share.task_tags.as_slice(), share.secs))
+                    .collect()
+            };
+            for (tags, secs) in parts {
+                for tag in tags {
+                    *totals.entry(tag.to_lowercase()).or_default() += secs;
+                }
+            }
+        }
+        let mut totals: Vec<_> = totals.into_iter().collect();
+        totals.sort_by_key(|(_, secs)| std::cmp::Reverse(*secs));
+        totals
+    }
+
+    /// Focus time per tag this week
+    pub fn week_focus_by_tag(&self) -> Vec<(String, u64)> {
+        self.focus_by_tag(self.day.week_start(), self.day.today())
+    }
+
     pub fn recent_sessions(&self, count: usize) -> Vec<&Session> {
         self.sessions.iter().rev().take(count).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::tasks::DEFAULT_PROJECT;
+
+    fn task(name: &str, tags: &[&str]) -> TaskData {
+        TaskData {
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..TaskData::new(name.to_string(), DEFAULT_PROJECT)
+        }
+    }
+
+    #[test]
+    fn stopwatch_and_timer_sessions_earn_no_pomodoro() {
+        let mut history = SessionHistory::default();
+        history.add(Session::new(SessionType::Work, 1500, None));
+        assert_eq!(history.today_pomodoro_count(), 1);
+
+        for mode in [SessionMode::Stopwatch, SessionMode::Timer] {
+            let mut session = Session::new(SessionType::Work, 3000, None);
+            session.mode = mode;
+            history.add(session);
+        }
+        assert_eq!(history.today_pomodoro_count(), 1);
+        assert_eq!(history.today_focus_secs(), 1500 + 2 * 3000);
+        assert_eq!(history.current_streak(), 1);
+    }
+
+    #[test]
+    fn name_only_sessions_are_linked_to_their_task() {
+        let tasks = [
+            task("Write report", &["work"]),
+            task("Email", &[]),
+            task("email", &[]),
+        ];
+        let mut history = SessionHistory::default();
+        for name in [
+            Some(" write Report"),
+            Some("Write report"),
+            Some("Email"),
+            Some("Gone"),
+            None,
+        ] {
+            history.add(Session::new(
+                SessionType::Work,
+                1500,
+                name.map(str::to_string),
+            ));
+        }
+
+        assert_eq!(history.link_tasks(&tasks), 2);
+        let linked: Vec<(Option<Uuid>, &[String])> = history
+            .sessions()
+            .iter()
+            .map(|s| (s.task_id, s.task_tags.as_slice()))
+            .collect();
+        let work = ["work".to_string()];
+        // Ambiguous and unknown names stay unlinked
+        assert_eq!(
+            linked,
+            [
+                (Some(tasks[0].id), &work[..]),
+                (Some(tasks[0].id), &work[..]),
+                (None, &[][..]),
+                (None, &[][..]),
+                (None, &[][..]),
+            ]
+        );
+        // Already linked sessions are left alone
+        assert_eq!(history.link_tasks(&tasks), 0);
+    }
+
+    #[test]
+    fn split_sessions_count_each_share() {
+        let share = |name: &str, tag: &str, secs| TaskShare {
+            task_id: Uuid::new_v4(),
+            subtask_id: None,
+            task_name: name.to_string(),
+            task_tags: vec![tag.to_string()],
+            secs,
+        };
+        let mut session = Session::new(SessionType::Work, 1500, None);
+        session.shares = vec![
+            share("Write report", "work", 900),
+            share("Email", "admin", 540),
+        ];
+        let first = session.shares[0].clone();
+        session.link_task(first.task_id, &first.task_name, &first.task_tags);
+
+        let mut history = SessionHistory::default();
+        history.add(session);
+        let today = Utc::now().date_naive();
+        let (from, to) = (today.pred_opt().unwrap(), today.succ_opt().unwrap());
+
+        let tasks: Vec<(String, u64)> = history
+            .focus_by_task(from, to)
+            .into_iter()
+            .map(|(_, name, secs)| (name, secs))
+            .collect();
+        assert_eq!(
+            tasks,
+            [
+                ("Write report".to_string(), 900),
+                ("Email".to_string(), 540)
+            ]
+        );
+        assert_eq!(
+            history.focus_by_tag(from, to),
+            [("work".to_string(), 900), ("admin".to_string(), 540)]
+        );
+    }
+
+    #[test]
+    fn short_shares_fold_into_the_largest() {
+        let share = |name: &str, secs| TaskShare {
+            task_id: Uuid::new_v4(),
+            subtask_id: None,
+            task_name: name.to_string(),
+            task_tags: Vec::new(),
+            secs,
+        };
+        let mut shares = vec![
+            share("Email", 520),
+            share("Glance", 45),
+            share("Write report", 900),
+        ];
+        settle_shares(&mut shares, 1500, 60);
+
+        let split: Vec<(&str, u64)> = shares
+            .iter()
+            .map(|s| (s.task_name.as_str(), s.secs))
+            .collect();
+        assert_eq!(split, [("Write report", 980), ("Email", 520)]);
+    }
+
+    #[test]
+    fn subtask_time_counts_toward_its_task() {
+        let report = task("Write report", &[]);
+        let share = |subtask_id: Option<Uuid>, secs| TaskShare {
+            task_id: report.id,
+            subtask_id,
+            task_name: report.name.clone(),
+            task_tags: Vec::new(),
+            secs,
+        };
+        let mut split = Session::new(SessionType::Work, 1500, None);
+        split.shares = vec![
+            share(Some(Uuid::new_v4()), 900),
+            share(Some(Uuid::new_v4()), 600),
+        ];
+        let mut whole = Session::new(SessionType::Work, 1500, None);
+        whole.link_task(report.id, &report.name, &report.tags);
+
+        let mut history = SessionHistory::default();
+        history.add(split);
+        history.add(whole);
+        let today = Utc::now().date_naive();
+        let tasks = history.focus_by_task(today, today);
+        assert_eq!(tasks, [(Some(report.id), "Write report".to_string(), 3000)]);
+    }
+
+    #[test]
+    fn renaming_a_tag_updates_session_tags() {
+        let mut session = Session::new(SessionType::Work, 1500, None);
+        session.task_tags = vec!["home".to_string(), "work/backend".to_string()];
+        let mut history = SessionHistory::default();
+        history.add(session);
+
+        assert_eq!(history.rename_tag("home", "work"), 1);
+        assert_eq!(history.rename_tag("work", "job"), 1);
+        assert_eq!(history.rename_tag("gone", "job"), 0);
+        assert_eq!(history.sessions()[0].task_tags, ["job", "job/backend"]);
+    }
+
+    #[test]
+    fn interrupted_work_lowers_the_completion_rate_but_keeps_its_focus_time() {
+        let mut history = SessionHistory::default();
+        assert_eq!(history.completion_rate(), None);
+        history.add(Session::new(SessionType::ShortBreak, 300, None));
+        history.add(Session::interrupted(
+            SessionType::ShortBreak,
+            60,
+            None,
+            Interruption::Skipped,
+        ));
+        assert_eq!(history.completion_rate(), None);
+
+        history.add(Session::new(SessionType::Work, 1500, None));
+        history.add(Session::new(SessionType::Work, 1500, None));
+        history.add(Session::new(SessionType::Work, 1500, None));
+        history.add(Session::interrupted(
+            SessionType::Work,
+            600,
+            None,
+            Interruption::Reset,
+        ));
+        assert_eq!(history.completion_rate(), Some(0.75));
+
+        assert_eq!(history.today_pomodoro_count(), 3);
+        assert_eq!(history.today_focus_secs(), 3 * 1500 + 600);
+    }
+
+    #[test]
+    fn profile_histories_keep_only_that_profiles_sessions() {
+        let in_profile = |profile: Option<&str>, secs| Session {
+            profile: profile.map(str::to_string),
+            ..Session::new(SessionType::Work, secs, None)
+        };
+        let mut history = SessionHistory::default();
+        history.add(in_profile(Some("deep-work"), 3000));
+        history.add(in_profile(Some("classic"), 1500));
+        history.add(in_profile(Some("deep-work"), 3000));
+        history.add(in_profile(None, 1500));
+
+        let deep = history.for_profile("deep-work");
+        assert_eq!(deep.sessions().len(), 2);
+        assert_eq!(deep.today_focus_secs(), 6000);
+        assert_eq!(deep.current_streak(), 1);
+
+        let unknown = history.for_profile("study");
+        assert!(unknown.sessions().is_empty());
+        assert_eq!((unknown.current_streak(), unknown.longest_streak()), (0, 0));
+    }
+}
@@ -0,0 +1,48 @@
+use super::Schema;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Snapshot of the in-flight timer, so a session survives the app closing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimerSnapshot {
+    pub saved_at: DateTime<Utc>,
+    /// "pomodoro", "timer" or "stopwatch"
+    pub timer_mode: String,
+    /// Countdown length in Timer mode
+    pub timer_secs: u64,
+    /// Session type of the current phase ("work", "short_break", "long_break")
+    pub timer_state: String,
+    pub remaining_secs: u64,
+    pub session_count: u8,
+    pub is_running: bool,
+    pub selected_task: Option<Uuid>,
+}
+
+impl TimerSnapshot {
+    fn file_path() -> Result<PathBuf> {
+        Ok(super::data_dir()?.join("state.json"))
+    }
+
+    /// Load the last snapshot, if there is a readable one
+    pub fn load() -> Result<Option<Self>> {
+        let path = Self::file_path()?;
+
+        if path.exists() {
+            super::read_versioned(&path, Schema::TimerState).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::file_path()?;
+        // A snapshot is short-lived, so it gets no backups
+        super::write_atomic(
+            &path,
+            super::to_versioned_json(Schema::TimerState, self)?.as_bytes(),
+        )
+    }
+}
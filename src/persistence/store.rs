@@ -0,0 +1,322 @@
+use super::sessions::Session;
+use super::Schema;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// What a store read back
+#[derive(Debug, Default)]
+pub struct Loaded {
+    pub sessions: Vec<Session>,
+    /// Set when damaged records had to be set aside
+    pub warning: Option<String>,
+}
+
+/// Where the session log is kept
+pub trait SessionStore {
+    /// Every readable session, in the order it was written
+    fn load(&self) -> Result<Loaded>;
+    /// Like `load`, but damaged records are only skipped and nothing is written
+    fn peek(&self) -> Result<Loaded>;
+    /// Add sessions to the end of the log
+    fn append(&mut self, sessions: &[Session]) -> Result<()>;
+    /// Replace the whole log (after an import, a reset or a migration)
+    fn rewrite(&mut self, sessions: &[Session]) -> Result<()>;
+}
+
+/// Append-only JSON Lines log: a `{"version": N}` header, then one session per line
+///
+/// A completed session costs one short write, and a crash mid-write can only
+/// damage the last line. Lines that don't parse are copied to a quarantine file
+/// and dropped from the log on load; logs in an older format are upgraded.
+pub struct JsonlStore {
+    path: PathBuf,
+}
+
+impl JsonlStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn header() -> Vec<u8> {
+        format!("{{\"version\":{}}}\n", Schema::Session.current_version()).into_bytes()
+    }
+}
+
+/// What a store's file held, before anything is written back
+struct Parsed {
+    sessions: Vec<Session>,
+    /// Records that couldn't be read, as they appear in the file
+    damaged: Vec<String>,
+    /// Written in an older version of the session format
+    outdated: bool,
+}
+
+impl Parsed {
+    /// The sessions, with a warning about any damaged records that were skipped
+    fn peeked(self, path: &Path) -> Loaded {
+        let warning = (!self.damaged.is_empty()).then(|| {
+            format!(
+                "{} damaged session record(s) in {} were skipped",
+                self.damaged.len(),
+                path.display()
+            )
+        });
+        Loaded {
+            sessions: self.sessions,
+            warning,
+        }
+    }
+}
+
+/// Write records that couldn't be read to `<name>.corrupt-<time>`, returning a warning that says where
+fn set_aside(path: &Path, damaged: &[String]) -> Result<String> {
+    let aside = path.with_file_name(format!(
+        "{}.corrupt-{}",
+        path.file_name().unwrap_or_default().to_string_lossy(),
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    fs::write(&aside, damaged.join("\n") + "\n")?;
+    Ok(format!(
+        "{} damaged session record(s) were set aside in {}",
+        damaged.len(),
+        aside.display()
+    ))
+}
+
+/// Parse one session record written in `version` of the session format
+fn read_record(mut value: Value, version: u32) -> Result<Session> {
+    super::upgrade(Schema::Session, &mut value, version)?;
+    Ok(serde_json::from_value(value)?)
+}
+
+impl JsonlStore {
+    fn parse(&self) -> Result<Parsed> {
+        let mut parsed = Parsed {
+            sessions: Vec::new(),
+            damaged: Vec::new(),
+            outdated: false,
+        };
+        if !self.path.exists() {
+            return Ok(parsed);
+        }
+        let file = File::open(&self.path)
+            .with_context(|| format!("could not open {}", self.path.display()))?;
+
+        // Logs from before versioning have no header and are version 1
+        let mut version = None;
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let Ok(value) = serde_json::from_str::<Value>(&line) else {
+                parsed.damaged.push(line);
+                continue;
+            };
+            if version.is_none() {
+                if value.get("version").is_some() && value.get("id").is_none() {
+                    let found = super::version_of(&value);
+                    super::check_not_newer(&self.path, Schema::Session, found)?;
+                    version = Some(found);
+                    continue;
+                }
+                version = Some(1);
+            }
+            match read_record(value, version.unwrap_or(1)) {
+                Ok(session) => parsed.sessions.push(session),
+                Err(_) => parsed.damaged.push(line),
+            }
+        }
+        parsed.outdated = version.is_some_and(|v| v < Schema::Session.current_version());
+        Ok(parsed)
+    }
+}
+
+impl SessionStore for JsonlStore {
+    fn load(&self) -> Result<Loaded> {
+        let Parsed {
+            sessions,
+            damaged,
+            outdated,
+        } = self.parse()?;
+        if damaged.is_empty() {
+            if outdated {
+                JsonlStore::new(self.path.clone()).rewrite(&sessions)?;
+            }
+            return Ok(Loaded {
+                sessions,
+                warning: None,
+            });
+        }
+
+        // Keep the bad lines for inspection, then rewrite the log without them
+        let warning = set_aside(&self.path, &damaged)?;
+        let mut store = JsonlStore::new(self.path.clone());
+        store.rewrite(&sessions)?;
+
+        Ok(Loaded {
+            sessions,
+            warning: Some(warning),
+        })
+    }
+
+    fn peek(&self) -> Result<Loaded> {
+        Ok(self.parse()?.peeked(&self.path))
+    }
+
+    fn append(&mut self, sessions: &[Session]) -> Result<()> {
+        super::create_parent(&self.path)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("could not open {}", self.path.display()))?;
+
+        // A new log starts with its header; otherwise start on a fresh line
+        // in case a previous write was cut short
+        let mut buffer = Vec::new();
+        if file.metadata()?.len() == 0 {
+            buffer = Self::header();
+        } else {
+            let mut last = [0u8; 1];
+            file.seek(SeekFrom::End(-1))?;
+            file.read_exact(&mut last)?;
+            if last[0] != b'\n' {
+                buffer.push(b'\n');
+            }
+        }
+
+        for session in sessions {
+            serde_json::to_writer(&mut buffer, session)?;
+            buffer.push(b'\n');
+        }
+        file.write_all(&buffer)?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    fn rewrite(&mut self, sessions: &[Session]) -> Result<()> {
+        let mut buffer = Self::header();
+        for session in sessions {
+            serde_json::to_writer(&mut buffer, session)?;
+            buffer.push(b'\n');
+        }
+        super::save_file(&self.path, &buffer)
+    }
+}
+
+/// The original `sessions.json` format: one pretty-printed object rewritten on every save
+pub struct JsonFileStore {
+    path: PathBuf,
+}
+
+impl JsonFileStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl JsonFileStore {
+    fn parse(&self, mut file: Value) -> Result<Parsed> {
+        let version = super::version_of(&file);
+        super::check_not_newer(&self.path, Schema::Session, version)?;
+        let records = match file.get_mut("sessions").map(Value::take) {
+            Some(Value::Array(records)) => records,
+            _ => Vec::new(),
+        };
+
+        let mut parsed = Parsed {
+            sessions: Vec::new(),
+            damaged: Vec::new(),
+            outdated: version < Schema::Session.current_version(),
+        };
+        for record in records {
+            let original = record.to_string();
+            match read_record(record, version) {
+                Ok(session) => parsed.sessions.push(session),
+                Err(_) => parsed.damaged.push(original),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+impl SessionStore for JsonFileStore {
+    fn load(&self) -> Result<Loaded> {
+        if !self.path.exists() {
+            return Ok(Loaded::default());
+        }
+        let Parsed {
+            sessions, damaged, ..
+        } = self.parse(super::read_json(&self.path)?)?;
+        let warning = if damaged.is_empty() {
+            None
+        } else {
+            Some(set_aside(&self.path, &damaged)?)
+        };
+        Ok(Loaded { sessions, warning })
+    }
+
+    fn peek(&self) -> Result<Loaded> {
+        if !self.path.exists() {
+            return Ok(Loaded::default());
+        }
+        let contents = fs::read_to_string(&self.path)
+            .with_context(|| format!("could not read {}", self.path.display()))?;
+        let file = serde_json::from_str(&contents)
+            .with_context(|| format!("{} is damaged", self.path.display()))?;
+        Ok(self.parse(file)?.peeked(&self.path))
+    }
+
+    fn append(&mut self, sessions: &[Session]) -> Result<()> {
+        let mut all = self.load()?.sessions;
+        all.extend_from_slice(sessions);
+        self.rewrite(&all)
+    }
+
+    fn rewrite(&mut self, sessions: &[Session]) -> Result<()> {
+        let contents = serde_json::to_string_pretty(&serde_json::json!({
+            "version": Schema::Session.current_version(),
+            "sessions": sessions,
+        }))?;
+        super::save_file(&self.path, contents.as_bytes())
+    }
+}
+
+/// Copy every session from one store into another, replacing its contents
+pub fn migrate(from: &dyn SessionStore, to: &mut dyn SessionStore) -> Result<usize> {
+    let sessions = from.load()?.sessions;
+    to.rewrite(&sessions)?;
+    Ok(sessions.len())
+}
+
+/// The session store in the data directory (~/.local/share/pomo-tui/sessions.jsonl)
+///
+/// An existing `sessions.json` is migrated on first use and kept as `sessions.json.bak`.
+pub fn open() -> Result<Box<dyn SessionStore>> {
+    let dir = super::data_dir()?;
+    let mut store = JsonlStore::new(dir.join("sessions.jsonl"));
+
+    let legacy_path = dir.join("sessions.json");
+    if !store.path.exists() && legacy_path.exists() {
+        let legacy = JsonFileStore::new(legacy_path.clone());
+        migrate(&legacy, &mut store).context("could not migrate sessions.json")?;
+        fs::rename(&legacy_path, dir.join("sessions.json.bak"))?;
+    }
+
+    Ok(Box::new(store))
+}
+
+/// Every readable session in the data directory, without migrating or repairing anything
+pub fn peek() -> Result<Loaded> {
+    let dir = super::data_dir()?;
+    let store = JsonlStore::new(dir.join("sessions.jsonl"));
+    if store.path.exists() {
+        return store.peek();
+    }
+    JsonFileStore::new(dir.join("sessions.json")).peek()
+}
@@ -0,0 +1,175 @@
+use super::config::Config;
+use anyhow::{bail, Context, Result};
+use chrono::{Datelike, NaiveDate, Weekday};
+use std::collections::BTreeMap;
+
+/// What counts as a streak day, and which days can't break a streak
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreakRules {
+    /// Completed pomodoros a day needs to count
+    pub min_pomodoros: u32,
+    pub freeze_weekdays: Vec<Weekday>,
+    /// Inclusive date ranges (a single day is a range of one)
+    pub freeze_dates: Vec<(NaiveDate, NaiveDate)>,
+}
+
+impl Default for StreakRules {
+    fn default() -> Self {
+        Self {
+            min_pomodoros: 1,
+            freeze_weekdays: Vec::new(),
+            freeze_dates: Vec::new(),
+        }
+    }
+}
+
+impl StreakRules {
+    pub fn new(config: &Config) -> Result<Self> {
+        let min_pomodoros = if config.streak_needs_goal {
+            config.daily_goal_pomodoros
+        } else {
+            config.streak_min_pomodoros
+        };
+
+        Ok(Self {
+            min_pomodoros: (min_pomodoros as u32).max(1),
+            freeze_weekdays: config
+                .streak_freeze_weekdays
+                .iter()
+                .map(|day| {
+                    day.parse::<Weekday>()
+                        .map_err(|_| anyhow::anyhow!("unknown weekday: {}", day))
+                })
+                .collect::<Result<_>>()?,
+            freeze_dates: config
+                .streak_freeze_dates
+                .iter()
+                .map(|range| parse_date_range(range))
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// Rules from the config, falling back to "one pomodoro a day" if they don't parse
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config).unwrap_or_default()
+    }
+
+    pub fn is_frozen(&self, date: NaiveDate) -> bool {
+        self.freeze_weekdays.contains(&date.weekday())
+            || self
+                .freeze_dates
+                .iter()
+                .any(|(from, to)| (*from..=*to).contains(&date))
+    }
+
+    /// Current and longest streak, given completed pomodoros per day
+    ///
+    /// A day that meets the rule extends the streak, a frozen day leaves it as it
+    /// is, and any other day ends it. Today only counts once it meets the rule.
+    pub fn streaks(&self, daily: &BTreeMap<NaiveDate, u32>, today: NaiveDate) -> (u32, u32) {
+        let qualifies = |date: NaiveDate| {
+            daily
+                .get(&date)
+                .is_some_and(|&count| count >= self.min_pomodoros)
+        };
+        let Some(first) = daily.keys().copied().find(|&date| qualifies(date)) else {
+            return (0, 0);
+        };
+
+        let (mut current, mut longest) = (0, 0);
+        for date in first.iter_days().take_while(|&date| date <= today) {
+            if qualifies(date) {
+                current += 1;
+                longest = longest.max(current);
+            } else if date != today && !self.is_frozen(date) {
+                current = 0;
+            }
+        }
+        (current, longest)
+    }
+}
+
+/// Parse "YYYY-MM-DD" or "YYYY-MM-DD..YYYY-MM-DD"
+fn parse_date_range(text: &str) -> Result<(NaiveDate, NaiveDate)> {
+    let parse = |date: &str| {
+        NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+            .with_context(|| format!("invalid freeze date: {}", date))
+    };
+    let (from, to) = match text.split_once("..") {
+        Some((from, to)) => (parse(from)?, parse(to)?),
+        None => (parse(text)?, parse(text)?),
+    };
+    if to < from {
+        bail!("freeze range ends before it starts: {}", text);
+    }
+    Ok((from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn daily(counts: &[(u32, u32)]) -> BTreeMap<NaiveDate, u32> {
+        counts.iter().map(|&(day, count)| (date(day), count)).collect()
+    }
+
+    #[test]
+    fn days_below_the_minimum_break_the_streak() {
+        let rules = StreakRules {
+            min_pomodoros: 2,
+            ..StreakRules::default()
+        };
+        let counts = daily(&[(10, 2), (11, 4), (12, 2), (13, 1), (14, 2), (15, 3)]);
+
+        assert_eq!(rules.streaks(&counts, date(15)), (2, 3));
+        // Today doesn't end the streak before it's over
+        assert_eq!(rules.streaks(&counts, date(16)), (2, 3));
+        assert_eq!(rules.streaks(&counts, date(17)), (0, 3));
+        assert_eq!(rules.streaks(&daily(&[(10, 1)]), date(10)), (0, 0));
+    }
+
+    #[test]
+    fn frozen_days_keep_the_streak_without_extending_it() {
+        let config = Config {
+            streak_freeze_weekdays: vec!["sat".to_string(), "Sunday".to_string()],
+            streak_freeze_dates: vec!["2026-10-14..2026-10-15".to_string()],
+            ..Config::default()
+        };
+        let rules = StreakRules::new(&config).unwrap();
+        // Friday, the weekend off, Monday and Tuesday, two days of leave, then Friday again
+        let counts = daily(&[(9, 1), (12, 1), (13, 1), (16, 1)]);
+
+        assert!(rules.is_frozen(date(10)) && rules.is_frozen(date(15)));
+        assert!(!rules.is_frozen(date(16)));
+        assert_eq!(rules.streaks(&counts, date(16)), (4, 4));
+        // The weekend after is frozen too, so only a missed Monday ends it
+        assert_eq!(rules.streaks(&counts, date(19)), (4, 4));
+        assert_eq!(rules.streaks(&counts, date(20)), (0, 4));
+    }
+
+    #[test]
+    fn the_minimum_comes_from_the_goal_when_the_streak_needs_it() {
+        let config = Config {
+            daily_goal_pomodoros: 6,
+            streak_min_pomodoros: 2,
+            streak_needs_goal: true,
+            ..Config::default()
+        };
+        assert_eq!(StreakRules::new(&config).unwrap().min_pomodoros, 6);
+
+        let bad_weekday = Config {
+            streak_freeze_weekdays: vec!["someday".to_string()],
+            ..Config::default()
+        };
+        assert!(StreakRules::new(&bad_weekday).is_err());
+        for range in ["2026-10-15..2026-10-14", "2026-13-01", "yesterday"] {
+            assert!(parse_date_range(range).is_err(), "{}", range);
+        }
+        assert_eq!(parse_date_range("2026-10-14").unwrap(), (date(14), date(14)));
+    }
+}
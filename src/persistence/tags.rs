@@ -1,15 +1,20 @@
+use super::Schema;
 use anyhow::Result;
 use chrono::{NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use std::fs;
 use std::path::PathBuf;
 
+/// Colors the tag manager cycles through (tags.json also takes any color name or "#rrggbb")
+pub const TAG_COLORS: [&str; 6] = ["red", "yellow", "green", "cyan", "blue", "magenta"];
+
 /// A learned tag with usage metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TagInfo {
     pub name: String,
     pub last_used: NaiveDate,
     pub count: u32,
+    #[serde(default)]
+    pub color: Option<String>,
 }
 
 impl TagInfo {
@@ -18,8 +23,51 @@ impl TagInfo {
             name,
             last_used: Utc::now().date_naive(),
             count: 1,
+            color: None,
+        }
+    }
+}
+
+/// Whether `tag` is `ancestor` or nested under it ("work/backend" is within "work")
+pub fn is_within(tag: &str, ancestor: &str) -> bool {
+    tag.get(..ancestor.len())
+        .is_some_and(|head| head.eq_ignore_ascii_case(ancestor))
+        && matches!(tag.as_bytes().get(ancestor.len()), None | Some(b'/'))
+}
+
+/// The tag one level up ("work" for "work/backend")
+pub fn parent(tag: &str) -> Option<&str> {
+    tag.rsplit_once('/').map(|(parent, _)| parent)
+}
+
+/// `tag` after renaming `from` to `to`, carrying nested tags along
+fn renamed(tag: &str, from: &str, to: &str) -> Option<String> {
+    is_within(tag, from).then(|| format!("{}{}", to, &tag[from.len()..]))
+}
+
+/// Rename a tag (and the tags nested under it) in a tag list, merging duplicates
+///
+/// Returns whether anything changed.
+pub fn rename_in(tags: &mut Vec<String>, from: &str, to: &str) -> bool {
+    if !tags.iter().any(|t| is_within(t, from)) {
+        return false;
+    }
+    let mut merged: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags.drain(..) {
+        let tag = renamed(&tag, from, to).unwrap_or(tag);
+        if !merged.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+            merged.push(tag);
         }
     }
+    *tags = merged;
+    true
+}
+
+/// Drop a tag from a tag list (tags nested under it stay); returns whether it was there
+pub fn remove_from(tags: &mut Vec<String>, name: &str) -> bool {
+    let before = tags.len();
+    tags.retain(|t| !t.eq_ignore_ascii_case(name));
+    tags.len() != before
 }
 
 /// Tag storage with learning
@@ -37,10 +85,7 @@ impl TagStore {
         let path = Self::file_path()?;
 
         if path.exists() {
-            let contents = fs::read_to_string(&path)?;
-            let mut store: TagStore = serde_json::from_str(&contents).unwrap_or_default();
-            store.cleanup_old_tags();
-            Ok(store)
+            super::read_versioned(&path, Schema::Tags)
         } else {
             let store = TagStore::default();
             store.save()?;
@@ -50,15 +95,87 @@ impl TagStore {
 
     pub fn save(&self) -> Result<()> {
         let path = Self::file_path()?;
-        let contents = serde_json::to_string_pretty(self)?;
-        fs::write(path, contents)?;
-        Ok(())
+        super::save_versioned(&path, Schema::Tags, self)
     }
 
-    /// Remove tags not used in 30 days
-    fn cleanup_old_tags(&mut self) {
-        let cutoff = Utc::now().date_naive() - chrono::Duration::days(7);
-        self.tags.retain(|t| t.last_used >= cutoff);
+    /// Forget tags not used in `retention_days` (0 keeps them forever)
+    ///
+    /// Tags still on a task and tags with a color are kept. Returns whether any were dropped.
+    pub fn forget_idle(&mut self, retention_days: u32, in_use: &[String]) -> bool {
+        if retention_days == 0 {
+            return false;
+        }
+        let cutoff = Utc::now().date_naive() - chrono::Duration::days(retention_days as i64);
+        let before = self.tags.len();
+        self.tags.retain(|t| {
+            t.last_used >= cutoff
+                || t.color.is_some()
+                || in_use.iter().any(|u| u.eq_ignore_ascii_case(&t.name))
+        });
+        self.tags.len() != before
+    }
+
+    fn find(&self, name: &str) -> Option<&TagInfo> {
+        self.tags.iter().find(|t| t.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The tag's color, or the nearest parent's ("work/backend" takes "work"'s)
+    pub fn color_of(&self, name: &str) -> Option<&str> {
+        let mut current = Some(name);
+        while let Some(tag) = current {
+            if let Some(color) = self.find(tag).and_then(|t| t.color.as_deref()) {
+                return Some(color);
+            }
+            current = parent(tag);
+        }
+        None
+    }
+
+    /// Step a tag's own color through `TAG_COLORS` and back to none
+    pub fn cycle_color(&mut self, name: &str) {
+        if self.find(name).is_none() {
+            let mut tag = TagInfo::new(name.to_string());
+            tag.count = 0;
+            self.tags.push(tag);
+        }
+        if let Some(tag) = self
+            .tags
+            .iter_mut()
+            .find(|t| t.name.eq_ignore_ascii_case(name))
+        {
+            let next = match tag.color.as_deref() {
+                None => Some(0),
+                Some(color) => TAG_COLORS.iter().position(|c| *c == color).map(|i| i + 1),
+            };
+            tag.color = next.and_then(|i| TAG_COLORS.get(i)).map(|c| c.to_string());
+        }
+    }
+
+    /// Rename a tag and the tags nested under it, merging into any that already exist
+    pub fn rename(&mut self, from: &str, to: &str) {
+        let mut merged: Vec<TagInfo> = Vec::with_capacity(self.tags.len());
+        for mut tag in self.tags.drain(..) {
+            if let Some(name) = renamed(&tag.name, from, to) {
+                tag.name = name;
+            }
+            match merged
+                .iter_mut()
+                .find(|t| t.name.eq_ignore_ascii_case(&tag.name))
+            {
+                Some(existing) => {
+                    existing.count += tag.count;
+                    existing.last_used = existing.last_used.max(tag.last_used);
+                    existing.color = existing.color.take().or(tag.color);
+                }
+                None => merged.push(tag),
+            }
+        }
+        merged.sort_by_key(|t| std::cmp::Reverse(t.count));
+        self.tags = merged;
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.tags.retain(|t| !t.name.eq_ignore_ascii_case(name));
     }
 
     /// Record usage of tags (learn new ones, update existing)
@@ -76,7 +193,7 @@ impl TagStore {
         }
 
         // Sort by count (most used first)
-        self.tags.sort_by(|a, b| b.count.cmp(&a.count));
+        self.tags.sort_by_key(|t| std::cmp::Reverse(t.count));
 
         let _ = self.save();
     }
@@ -110,6 +227,51 @@ impl TagStore {
 
         None
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn tag(name: &str, count: u32) -> TagInfo {
+        TagInfo {
+            count,
+            ..TagInfo::new(name.to_string())
+        }
+    }
+
+    #[test]
+    fn renaming_a_tag_moves_nested_tags_and_merges() {
+        let mut store = TagStore {
+            tags: vec![tag("work", 12), tag("home", 2), tag("work/backend", 1)],
+        };
+        store.cycle_color("work");
+        assert_eq!(store.color_of("work/backend"), Some("red"));
 
+        store.rename("home", "work");
+        store.rename("work", "job");
+        let names: Vec<(&str, u32)> = store
+            .tags
+            .iter()
+            .map(|t| (t.name.as_str(), t.count))
+            .collect();
+        assert_eq!(names, [("job", 14), ("job/backend", 1)]);
+        assert_eq!(store.color_of("job/backend"), Some("red"));
+    }
 
+    #[test]
+    fn idle_tags_are_forgotten_unless_colored_or_in_use() {
+        let long_ago = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        let mut store = TagStore {
+            tags: vec![tag("job", 14), tag("errands", 1), tag("old", 3)],
+        };
+        store.tags.iter_mut().for_each(|t| t.last_used = long_ago);
+        store.cycle_color("job");
+
+        assert!(!store.forget_idle(0, &[]));
+        assert!(store.forget_idle(30, &["Errands".to_string()]));
+        let names: Vec<&str> = store.tags.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["job", "errands"]);
+    }
 }
@@ -1,10 +1,196 @@
+use super::Schema;
 use anyhow::Result;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Local, NaiveDate, Utc, Weekday};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
 use serde::{Deserialize, Serialize};
-use std::fs;
+use std::fmt;
 use std::path::PathBuf;
 use uuid::Uuid;
 
+/// How urgent a task is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Urgent,
+}
+
+impl Priority {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "low" | "l" => Some(Priority::Low),
+            "normal" | "n" | "medium" | "med" => Some(Priority::Normal),
+            "high" | "h" | "!" => Some(Priority::High),
+            "urgent" | "u" | "!!" => Some(Priority::Urgent),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Priority::Low => "low",
+            Priority::Normal => "normal",
+            Priority::High => "high",
+            Priority::Urgent => "urgent",
+        }
+    }
+
+    /// Marker shown before a task name ("" for normal priority)
+    pub fn marker(&self) -> &'static str {
+        match self {
+            Priority::Low => "↓",
+            Priority::Normal => "",
+            Priority::High => "!",
+            Priority::Urgent => "‼",
+        }
+    }
+}
+
+/// Something that happened to a task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskEventKind {
+    Created,
+    Edited,
+    Completed,
+    Reopened,
+    PomodoroSpent,
+    /// Brought back from the archive
+    Restored,
+}
+
+/// One entry in a task's history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskEvent {
+    pub at: DateTime<Utc>,
+    pub kind: TaskEventKind,
+}
+
+impl TaskEvent {
+    pub fn now(kind: TaskEventKind) -> Self {
+        Self {
+            at: Utc::now(),
+            kind,
+        }
+    }
+}
+
+/// When a task was created, first worked on and (if it still is) completed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lifecycle {
+    pub created: DateTime<Utc>,
+    pub started: Option<DateTime<Utc>>,
+    pub completed: Option<DateTime<Utc>>,
+}
+
+impl Lifecycle {
+    pub fn from_events(created: DateTime<Utc>, events: &[TaskEvent]) -> Self {
+        let mut lifecycle = Self {
+            created,
+            started: None,
+            completed: None,
+        };
+        for event in events {
+            match event.kind {
+                TaskEventKind::PomodoroSpent if lifecycle.started.is_none() => {
+                    lifecycle.started = Some(event.at)
+                }
+                TaskEventKind::Completed => lifecycle.completed = Some(event.at),
+                TaskEventKind::Reopened => lifecycle.completed = None,
+                _ => {}
+            }
+        }
+        lifecycle
+    }
+
+    /// Time between creating a task and its first pomodoro
+    pub fn waited(&self) -> Option<chrono::Duration> {
+        self.started.map(|started| started - self.created)
+    }
+
+    /// Time from the first pomodoro (or creation, if it never had one) to completion
+    pub fn cycle_time(&self) -> Option<chrono::Duration> {
+        self.completed
+            .map(|completed| completed - self.started.unwrap_or(self.created))
+    }
+}
+
+/// Averages over a task list, for the dashboard
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TaskFlow {
+    /// Tasks completed in the last 7 days
+    pub completed_this_week: usize,
+    pub average_cycle_time: Option<chrono::Duration>,
+    pub average_wait: Option<chrono::Duration>,
+    pub open: usize,
+    /// Age of the oldest open task
+    pub oldest_open: Option<chrono::Duration>,
+}
+
+impl TaskFlow {
+    pub fn new(lifecycles: impl IntoIterator<Item = Lifecycle>, now: DateTime<Utc>) -> Self {
+        let average = |durations: &[chrono::Duration]| {
+            (!durations.is_empty())
+                .then(|| durations.iter().sum::<chrono::Duration>() / durations.len() as i32)
+        };
+
+        let mut flow = TaskFlow::default();
+        let (mut cycle_times, mut waits) = (Vec::new(), Vec::new());
+        for lifecycle in lifecycles {
+            waits.extend(lifecycle.waited());
+            match lifecycle.completed {
+                Some(completed) => {
+                    cycle_times.extend(lifecycle.cycle_time());
+                    if now - completed <= chrono::Duration::days(7) {
+                        flow.completed_this_week += 1;
+                    }
+                }
+                None => {
+                    flow.open += 1;
+                    let age = now - lifecycle.created;
+                    flow.oldest_open = Some(flow.oldest_open.map_or(age, |oldest| oldest.max(age)));
+                }
+            }
+        }
+        flow.average_cycle_time = average(&cycle_times);
+        flow.average_wait = average(&waits);
+        flow
+    }
+}
+
+/// A checklist step under a task
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Subtask {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(default)]
+    pub completed: bool,
+    #[serde(default)]
+    pub pomodoros_spent: u32,
+}
+
+impl Subtask {
+    pub fn new(name: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            completed: false,
+            pomodoros_spent: 0,
+        }
+    }
+}
+
+/// The list tasks go to when no other exists
+pub const DEFAULT_PROJECT: &str = "Inbox";
+
+fn default_project() -> String {
+    DEFAULT_PROJECT.to_string()
+}
+
 /// A task item (serializable version)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskData {
@@ -14,14 +200,149 @@ pub struct TaskData {
     pub pomodoros_spent: u32,
     pub tags: Vec<String>,
     pub created_at: DateTime<Utc>,
+    /// The list the task belongs to
+    #[serde(default = "default_project")]
+    pub project: String,
+    /// Pomodoros the task is expected to take
+    #[serde(default)]
+    pub estimate: Option<u32>,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub due: Option<NaiveDate>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Oldest first, starting with `Created`
+    #[serde(default)]
+    pub events: Vec<TaskEvent>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subtasks: Vec<Subtask>,
+    /// Whether the task pane shows the subtasks
+    #[serde(default)]
+    pub expanded: bool,
 }
 
+impl TaskData {
+    /// A new open task, with its creation as the first event
+    pub fn new(name: String, project: &str) -> Self {
+        let created = TaskEvent::now(TaskEventKind::Created);
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            completed: false,
+            pomodoros_spent: 0,
+            tags: Vec::new(),
+            created_at: created.at,
+            project: project.to_string(),
+            estimate: None,
+            priority: Priority::default(),
+            due: None,
+            description: None,
+            completed_at: None,
+            events: vec![created],
+            subtasks: Vec::new(),
+            expanded: false,
+        }
+    }
+
+    /// Replace everything the task prompt can edit
+    pub fn apply_input(&mut self, input: TaskInput) {
+        self.name = input.name;
+        self.tags = input.tags;
+        self.priority = input.priority.unwrap_or_default();
+        self.estimate = input.estimate;
+        self.due = input.due;
+        self.description = input.description;
+    }
+
+    /// Credit a pomodoro to the task itself, or to one of its subtasks
+    pub fn spend_pomodoro(&mut self, subtask_id: Option<Uuid>) {
+        match subtask_id.and_then(|id| self.subtasks.iter_mut().find(|s| s.id == id)) {
+            Some(subtask) => subtask.pomodoros_spent += 1,
+            None => self.pomodoros_spent += 1,
+        }
+        self.record(TaskEventKind::PomodoroSpent);
+    }
 
+    /// Pomodoros spent on the task and all of its subtasks
+    pub fn total_pomodoros(&self) -> u32 {
+        self.pomodoros_spent + self.subtasks.iter().map(|s| s.pomodoros_spent).sum::<u32>()
+    }
+
+    pub fn record(&mut self, kind: TaskEventKind) {
+        self.events.push(TaskEvent::now(kind));
+    }
+
+    /// Complete or reopen the task; finishing a task finishes its checklist too
+    pub fn set_completed(&mut self, completed: bool) {
+        self.completed = completed;
+        self.completed_at = completed.then(Utc::now);
+        if completed {
+            self.subtasks.iter_mut().for_each(|s| s.completed = true);
+        }
+        self.record(if completed {
+            TaskEventKind::Completed
+        } else {
+            TaskEventKind::Reopened
+        });
+    }
+
+    /// Completed and total subtasks, if there are any
+    pub fn checklist_progress(&self) -> Option<(usize, usize)> {
+        (!self.subtasks.is_empty()).then(|| {
+            let done = self.subtasks.iter().filter(|s| s.completed).count();
+            (done, self.subtasks.len())
+        })
+    }
+
+    pub fn toggle_subtask(&mut self, index: usize) {
+        if let Some(subtask) = self.subtasks.get_mut(index) {
+            subtask.completed = !subtask.completed;
+            self.roll_up();
+        }
+    }
+
+    /// Complete the task once every subtask is done, and reopen it when one isn't
+    pub fn roll_up(&mut self) {
+        if let Some((done, total)) = self.checklist_progress() {
+            let all_done = done == total;
+            if all_done != self.completed {
+                self.set_completed(all_done);
+            }
+        }
+    }
+
+    pub fn lifecycle(&self) -> Lifecycle {
+        Lifecycle::from_events(self.created_at, &self.events)
+    }
+}
 
 /// Task list storage
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskStore {
     pub tasks: Vec<TaskData>,
+    /// Names of the task lists, in the order they were created
+    #[serde(default = "default_projects")]
+    pub projects: Vec<String>,
+    /// The list shown in the task pane and used by `tasks add`
+    #[serde(default = "default_project")]
+    pub active_project: String,
+}
+
+fn default_projects() -> Vec<String> {
+    vec![default_project()]
+}
+
+impl Default for TaskStore {
+    fn default() -> Self {
+        Self {
+            tasks: Vec::new(),
+            projects: default_projects(),
+            active_project: default_project(),
+        }
+    }
 }
 
 impl TaskStore {
@@ -33,9 +354,7 @@ impl TaskStore {
         let path = Self::file_path()?;
 
         if path.exists() {
-            let contents = fs::read_to_string(&path)?;
-            let store: TaskStore = serde_json::from_str(&contents).unwrap_or_default();
-            Ok(store)
+            Ok(super::read_versioned::<TaskStore>(&path, Schema::Tasks)?.with_every_project())
         } else {
             let store = TaskStore::default();
             store.save()?;
@@ -43,27 +362,484 @@ impl TaskStore {
         }
     }
 
+    /// Read the task list without writing anything (a missing file is an empty list)
+    pub fn peek() -> Result<Self> {
+        let path = Self::file_path()?;
+        if path.exists() {
+            Ok(super::peek_versioned::<TaskStore>(&path, Schema::Tasks)?.with_every_project())
+        } else {
+            Ok(TaskStore::default())
+        }
+    }
+
+    /// Every list a task points to is listed, and so is the active one
+    fn with_every_project(mut self) -> Self {
+        for project in self
+            .tasks
+            .iter()
+            .map(|t| t.project.clone())
+            .collect::<Vec<_>>()
+        {
+            add_project(&mut self.projects, &project);
+        }
+        add_project(&mut self.projects, &self.active_project.clone());
+        self
+    }
+
     pub fn save(&self) -> Result<()> {
         let path = Self::file_path()?;
-        let contents = serde_json::to_string_pretty(self)?;
-        fs::write(path, contents)?;
+        super::save_versioned(&path, Schema::Tasks, self)
+    }
+
+    /// Make a list active, creating it if it doesn't exist yet
+    pub fn use_project(&mut self, name: &str) {
+        add_project(&mut self.projects, name);
+        self.active_project = name.to_string();
+    }
+
+    /// Tasks in the active list, with their indices into `tasks`
+    pub fn active_tasks(&self) -> impl Iterator<Item = (usize, &TaskData)> {
+        self.tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.project == self.active_project)
+    }
+}
+
+/// Add a list name unless it is already there; returns whether it was added
+pub fn add_project(projects: &mut Vec<String>, name: &str) -> bool {
+    if projects.iter().any(|p| p == name) {
+        return false;
+    }
+    projects.push(name.to_string());
+    true
+}
+
+/// Order of the task pane
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TaskSort {
+    /// The order tasks were added in, rearranged by hand
+    #[default]
+    Manual,
+    /// Most pomodoros first
+    Pomodoros,
+    /// Newest first
+    Created,
+    /// Alphabetically by first tag, untagged last
+    Tag,
+}
+
+impl TaskSort {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "manual" => Some(TaskSort::Manual),
+            "pomodoros" => Some(TaskSort::Pomodoros),
+            "created" => Some(TaskSort::Created),
+            "tag" => Some(TaskSort::Tag),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TaskSort::Manual => "manual",
+            TaskSort::Pomodoros => "pomodoros",
+            TaskSort::Created => "created",
+            TaskSort::Tag => "tag",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            TaskSort::Manual => TaskSort::Pomodoros,
+            TaskSort::Pomodoros => TaskSort::Created,
+            TaskSort::Created => TaskSort::Tag,
+            TaskSort::Tag => TaskSort::Manual,
+        }
+    }
+}
+
+/// A task pane search such as "report #work is:open"
+///
+/// `#tag` keeps tasks with a tag starting with that text, `is:open` and `is:done`
+/// filter by completion, and the remaining words are fuzzy-matched against the name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskQuery {
+    pub text: String,
+    pub tags: Vec<String>,
+    pub completed: Option<bool>,
+}
+
+impl TaskQuery {
+    pub fn parse(query: &str) -> Self {
+        let mut parsed = TaskQuery::default();
+        let mut words = Vec::new();
+        for word in query.split_whitespace() {
+            match word {
+                "is:open" | "is:todo" => parsed.completed = Some(false),
+                "is:done" => parsed.completed = Some(true),
+                _ => match word.strip_prefix('#').filter(|t| !t.is_empty()) {
+                    Some(tag) => parsed.tags.push(tag.to_lowercase()),
+                    None => words.push(word),
+                },
+            }
+        }
+        parsed.text = words.join(" ");
+        parsed
+    }
+
+    /// How well a task matches (higher is better), or None if it is filtered out
+    pub fn score(
+        &self,
+        matcher: &SkimMatcherV2,
+        name: &str,
+        tags: &[String],
+        completed: bool,
+    ) -> Option<i64> {
+        let kept = self.completed.is_none_or(|c| c == completed)
+            && self.tags.iter().all(|wanted| {
+                tags.iter()
+                    .any(|tag| tag.to_lowercase().starts_with(wanted))
+            });
+        if !kept {
+            None
+        } else if self.text.is_empty() {
+            Some(0)
+        } else {
+            matcher.fuzzy_match(name, &self.text)
+        }
+    }
+}
+
+/// Everything that can be typed into the task prompt
+///
+/// "Write report #work !high ~4 @2026-10-20 -- outline first" gives the name
+/// "Write report", tag "work", high priority, an estimate of 4 pomodoros, a due
+/// date and the description "outline first". Words that don't parse stay in the name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskInput {
+    pub name: String,
+    pub tags: Vec<String>,
+    pub priority: Option<Priority>,
+    pub estimate: Option<u32>,
+    pub due: Option<NaiveDate>,
+    pub description: Option<String>,
+}
+
+/// Back to the prompt syntax, for editing
+impl fmt::Display for TaskInput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        for tag in &self.tags {
+            write!(f, " #{}", tag)?;
+        }
+        if let Some(priority) = self.priority.filter(|p| *p != Priority::Normal) {
+            write!(f, " !{}", priority.name())?;
+        }
+        if let Some(estimate) = self.estimate {
+            write!(f, " ~{}", estimate)?;
+        }
+        if let Some(due) = self.due {
+            write!(f, " @{}", due.format("%Y-%m-%d"))?;
+        }
+        if let Some(description) = &self.description {
+            write!(f, " -- {}", description)?;
+        }
         Ok(())
     }
 }
 
-/// Parse task input for tags (e.g., "Buy milk #shopping #urgent")
-/// Returns (clean_name, tags)
-pub fn parse_task_input(input: &str) -> (String, Vec<String>) {
-    let mut tags = Vec::new();
+/// Parse a due date: YYYY-MM-DD, "today", "tomorrow" or a weekday (the next one)
+pub fn parse_due_date(text: &str) -> Option<NaiveDate> {
+    let today = Local::now().date_naive();
+    match text.to_ascii_lowercase().as_str() {
+        "today" => return Some(today),
+        "tomorrow" | "tmr" => return today.succ_opt(),
+        _ => {}
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return Some(date);
+    }
+    let weekday = text.parse::<Weekday>().ok()?;
+    let ahead =
+        (weekday.num_days_from_monday() as i64 - today.weekday().num_days_from_monday() as i64 + 6)
+            % 7
+            + 1;
+    Some(today + chrono::Duration::days(ahead))
+}
+
+/// Parse the task prompt: name, #tags, !priority, ~estimate, @due and "-- description"
+pub fn parse_task_input(input: &str) -> TaskInput {
+    let (fields, description) = match input.split_once(" -- ") {
+        Some((fields, description)) => (fields, Some(description.trim())),
+        None => (input.strip_suffix(" --").unwrap_or(input), None),
+    };
+
+    let mut parsed = TaskInput {
+        description: description.filter(|d| !d.is_empty()).map(str::to_string),
+        ..Default::default()
+    };
     let mut name_parts = Vec::new();
 
-    for word in input.split_whitespace() {
-        if word.starts_with('#') && word.len() > 1 {
-            tags.push(word[1..].to_string());
+    for word in fields.split_whitespace() {
+        if let Some(tag) = word.strip_prefix('#').filter(|t| !t.is_empty()) {
+            parsed.tags.push(tag.to_string());
+        } else if let Some(priority) = word.strip_prefix('!').and_then(Priority::from_name) {
+            parsed.priority = Some(priority);
+        } else if let Some(estimate) = word
+            .strip_prefix('~')
+            .and_then(|n| n.parse().ok())
+            .filter(|&n| n > 0)
+        {
+            parsed.estimate = Some(estimate);
+        } else if let Some(due) = word.strip_prefix('@').and_then(parse_due_date) {
+            parsed.due = Some(due);
         } else {
             name_parts.push(word);
         }
     }
 
-    (name_parts.join(" "), tags)
+    parsed.name = name_parts.join(" ");
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn the_prompt_splits_into_fields() {
+        let input = parse_task_input(
+            "Write #work report !high ~4 @2026-10-20 #docs -- outline first, then -- draft",
+        );
+        assert_eq!(
+            input,
+            TaskInput {
+                name: "Write report".to_string(),
+                tags: vec!["work".to_string(), "docs".to_string()],
+                priority: Some(Priority::High),
+                estimate: Some(4),
+                due: NaiveDate::from_ymd_opt(2026, 10, 20),
+                description: Some("outline first, then -- draft".to_string()),
+            }
+        );
+        assert_eq!(parse_task_input(&input.to_string()), input);
+    }
+
+    #[test]
+    fn words_that_dont_parse_stay_in_the_name() {
+        let input = parse_task_input("Fix # bug !maybe ~0 ~x @someday C#--");
+        assert_eq!(input.name, "Fix # bug !maybe ~0 ~x @someday C#--");
+        assert_eq!(
+            input,
+            TaskInput {
+                name: input.name.clone(),
+                ..TaskInput::default()
+            }
+        );
+
+        assert_eq!(parse_task_input("Call mom --").description, None);
+        assert_eq!(parse_task_input("Call mom --   ").description, None);
+        assert_eq!(
+            parse_task_input("!u Ship it").priority,
+            Some(Priority::Urgent)
+        );
+    }
+
+    #[test]
+    fn due_dates_can_be_relative() {
+        let today = Local::now().date_naive();
+        assert_eq!(parse_due_date("Today"), Some(today));
+        assert_eq!(parse_due_date("tmr"), today.succ_opt());
+        assert_eq!(parse_due_date("2026-02-30"), None);
+        assert_eq!(parse_due_date("soon"), None);
+
+        // A weekday is always the next one, a week ahead if it's today
+        let friday = parse_due_date("fri").unwrap();
+        assert_eq!(friday.weekday(), Weekday::Fri);
+        assert!((1..=7).contains(&(friday - today).num_days()));
+        let same_day = parse_due_date(&today.weekday().to_string()).unwrap();
+        assert_eq!(same_day - today, chrono::Duration::days(7));
+    }
+
+    fn tags(names: &[&str]) -> Vec<String> {
+        names.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn searches_split_into_words_tags_and_status() {
+        assert_eq!(
+            TaskQuery::parse("  write #Work is:open  report # "),
+            TaskQuery {
+                text: "write report #".to_string(),
+                tags: tags(&["work"]),
+                completed: Some(false),
+            }
+        );
+        assert_eq!(TaskQuery::parse("is:done").completed, Some(true));
+        assert_eq!(TaskQuery::parse("is:todo").completed, Some(false));
+    }
+
+    #[test]
+    fn searches_filter_by_tag_prefix_and_status_then_rank_by_name() {
+        let matcher = SkimMatcherV2::default();
+        let work = tags(&["Work/backend", "urgent"]);
+
+        let query = TaskQuery::parse("#work #urg");
+        assert_eq!(query.score(&matcher, "Anything", &work, false), Some(0));
+        assert_eq!(
+            query.score(&matcher, "Anything", &tags(&["work"]), false),
+            None
+        );
+        assert_eq!(
+            TaskQuery::parse("#backend").score(&matcher, "Anything", &work, false),
+            None
+        );
+
+        let open = TaskQuery::parse("is:open");
+        assert_eq!(open.score(&matcher, "Done already", &[], true), None);
+        assert_eq!(open.score(&matcher, "Still open", &[], false), Some(0));
+
+        let query = TaskQuery::parse("rep");
+        let exact = query.score(&matcher, "Report", &[], false).unwrap();
+        let scattered = query
+            .score(&matcher, "Review the plan", &[], false)
+            .unwrap();
+        assert!(exact > scattered);
+        assert_eq!(query.score(&matcher, "Groceries", &[], false), None);
+    }
+
+    fn event(minutes: i64, kind: TaskEventKind) -> TaskEvent {
+        TaskEvent {
+            at: start() + chrono::Duration::minutes(minutes),
+            kind,
+        }
+    }
+
+    fn start() -> DateTime<Utc> {
+        "2026-10-01T09:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn lifecycles_start_at_the_first_pomodoro_and_end_at_the_last_completion() {
+        let events = [
+            event(30, TaskEventKind::Edited),
+            event(60, TaskEventKind::PomodoroSpent),
+            event(90, TaskEventKind::PomodoroSpent),
+            event(100, TaskEventKind::Completed),
+            event(110, TaskEventKind::Reopened),
+            event(160, TaskEventKind::Completed),
+        ];
+        let lifecycle = Lifecycle::from_events(start(), &events);
+        assert_eq!(lifecycle.waited(), Some(chrono::Duration::minutes(60)));
+        assert_eq!(lifecycle.cycle_time(), Some(chrono::Duration::minutes(100)));
+
+        // Reopening undoes the completion; a task done without pomodoros cycles from creation
+        let reopened = Lifecycle::from_events(start(), &events[..5]);
+        assert_eq!(reopened.completed, None);
+        assert_eq!(reopened.cycle_time(), None);
+        let untimed = Lifecycle::from_events(start(), &[event(45, TaskEventKind::Completed)]);
+        assert_eq!(untimed.waited(), None);
+        assert_eq!(untimed.cycle_time(), Some(chrono::Duration::minutes(45)));
+    }
+
+    #[test]
+    fn task_flow_averages_completed_tasks_and_ages_open_ones() {
+        let day = chrono::Duration::days(1);
+        let lifecycle = |created: i64, started: Option<i64>, completed: Option<i64>| Lifecycle {
+            created: start() + day * created as i32,
+            started: started.map(|d| start() + day * d as i32),
+            completed: completed.map(|d| start() + day * d as i32),
+        };
+        let now = start() + day * 20;
+        let flow = TaskFlow::new(
+            [
+                lifecycle(0, Some(2), Some(4)),
+                lifecycle(10, Some(11), Some(15)),
+                lifecycle(12, None, Some(18)),
+                lifecycle(5, Some(9), None),
+                lifecycle(16, None, None),
+            ],
+            now,
+        );
+        assert_eq!(
+            flow,
+            TaskFlow {
+                completed_this_week: 2,
+                average_cycle_time: Some(day * 4),
+                average_wait: Some(day * 7 / 3),
+                open: 2,
+                oldest_open: Some(day * 15),
+            }
+        );
+        assert_eq!(TaskFlow::new([], now), TaskFlow::default());
+    }
+
+    fn checklist(steps: &[&str]) -> TaskData {
+        let mut task = TaskData::new("Release".to_string(), DEFAULT_PROJECT);
+        task.subtasks = steps.iter().map(|s| Subtask::new(s.to_string())).collect();
+        task
+    }
+
+    fn kinds(task: &TaskData) -> Vec<TaskEventKind> {
+        task.events.iter().map(|e| e.kind).collect()
+    }
+
+    #[test]
+    fn finishing_the_last_subtask_completes_the_task_and_undoing_one_reopens_it() {
+        let mut task = checklist(&["Tag", "Build", "Publish"]);
+        assert_eq!(task.checklist_progress(), Some((0, 3)));
+
+        task.toggle_subtask(0);
+        task.toggle_subtask(1);
+        assert!(!task.completed);
+        task.toggle_subtask(2);
+        assert!(task.completed && task.completed_at.is_some());
+        assert_eq!(task.checklist_progress(), Some((3, 3)));
+
+        task.toggle_subtask(1);
+        assert!(!task.completed && task.completed_at.is_none());
+        task.toggle_subtask(7);
+        assert_eq!(task.checklist_progress(), Some((2, 3)));
+        assert_eq!(
+            kinds(&task),
+            [
+                TaskEventKind::Created,
+                TaskEventKind::Completed,
+                TaskEventKind::Reopened
+            ]
+        );
+    }
+
+    #[test]
+    fn completing_a_task_ticks_off_its_checklist() {
+        let mut task = checklist(&["Tag", "Build"]);
+        task.set_completed(true);
+        assert_eq!(task.checklist_progress(), Some((2, 2)));
+
+        // Reopening leaves the checklist alone, and a task without one never rolls up
+        task.set_completed(false);
+        assert_eq!(task.checklist_progress(), Some((2, 2)));
+        let mut plain = checklist(&[]);
+        plain.roll_up();
+        assert!(!plain.completed);
+        assert_eq!(plain.checklist_progress(), None);
+    }
+
+    #[test]
+    fn pomodoros_on_subtasks_count_toward_the_task() {
+        let mut task = checklist(&["Tag", "Build"]);
+        let build = task.subtasks[1].id;
+        task.spend_pomodoro(Some(build));
+        task.spend_pomodoro(Some(build));
+        task.spend_pomodoro(None);
+        // A subtask that has since been removed credits the task itself
+        task.spend_pomodoro(Some(Uuid::new_v4()));
+
+        assert_eq!(task.subtasks[1].pomodoros_spent, 2);
+        assert_eq!(task.pomodoros_spent, 2);
+        assert_eq!(task.total_pomodoros(), 4);
+        assert!(task.lifecycle().started.is_some());
+    }
 }
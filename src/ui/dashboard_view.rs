@@ -1,9 +1,12 @@
-use crate::app::App;
+use crate::app::{App, InputMode};
+use crate::keymap::{Action, KeyContext};
+use crate::persistence::{export::ExportFormat, sessions::SessionType};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Style, Stylize},
+    style::{Style, Stylize},
+    symbols::border,
     text::{Line, Span},
-    widgets::{Bar, BarChart, BarGroup, Block, Borders, Cell, Paragraph, Row, Table},
+    widgets::{Bar, BarChart, BarGroup, Block, Borders, Cell, Clear, Paragraph, Row, Table, Wrap},
     Frame,
 };
 
@@ -22,10 +25,15 @@ pub fn draw(frame: &mut Frame, app: &App) {
 
     draw_header(frame, chunks[0], app);
     draw_main_content(frame, chunks[1], app);
-    draw_footer(frame, chunks[2]);
+    draw_footer(frame, chunks[2], app);
+
+    if app.input_mode == InputMode::Exporting {
+        draw_export_popup(frame, app);
+    }
 }
 
 fn draw_header(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
@@ -35,27 +43,32 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App) {
         ])
         .split(area);
 
-    let streak_display = if app.session_history.current_streak > 0 {
-        format!(" 🔥{}", app.session_history.current_streak)
+    let history = app.dashboard_history();
+    let streak_display = if history.current_streak() > 0 {
+        format!(" 🔥{}", history.current_streak())
     } else {
         String::new()
     };
 
     let title = Paragraph::new(Line::from(vec![
-        Span::styled("🍅 ", Style::default().fg(Color::Red)),
-        Span::styled("POMO-TUI", Style::default().fg(Color::Cyan).bold()),
-        Span::styled(streak_display, Style::default().fg(Color::Yellow)),
+        Span::styled("🍅 ", Style::default().fg(theme.danger)),
+        Span::styled("POMO-TUI", Style::default().fg(theme.accent).bold()),
+        Span::styled(streak_display, Style::default().fg(theme.highlight)),
     ]))
     .alignment(Alignment::Left);
     frame.render_widget(title, chunks[0]);
 
-    let mode = Paragraph::new("📊 Dashboard")
-        .style(Style::default().fg(Color::Yellow))
+    let title = match &app.profile_filter {
+        Some(profile) => format!("📊 Dashboard · {}", profile),
+        None => "📊 Dashboard".to_string(),
+    };
+    let mode = Paragraph::new(title)
+        .style(Style::default().fg(theme.highlight))
         .alignment(Alignment::Center);
     frame.render_widget(mode, chunks[1]);
 
     let help = Paragraph::new("Press 1 for Timer")
-        .style(Style::default().fg(Color::DarkGray))
+        .style(Style::default().fg(theme.dim))
         .alignment(Alignment::Right);
     frame.render_widget(help, chunks[2]);
 }
@@ -70,9 +83,21 @@ fn draw_main_content(frame: &mut Frame, area: Rect, app: &App) {
         ])
         .split(area);
 
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(40), Constraint::Length(28)])
+        .split(chunks[2]);
+
     draw_stats_cards(frame, chunks[0], app);
     draw_weekly_chart(frame, chunks[1], app);
-    draw_recent_sessions(frame, chunks[2], app);
+    let side = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(7), Constraint::Min(3)])
+        .split(bottom[1]);
+
+    draw_recent_sessions(frame, bottom[0], app);
+    draw_task_flow(frame, side[0], app);
+    draw_project_breakdown(frame, side[1], app);
 }
 
 fn format_duration(secs: u64) -> String {
@@ -85,7 +110,84 @@ fn format_duration(secs: u64) -> String {
     }
 }
 
+/// A longer span of time: "3d 4h", "5h 10m" or "12m"
+fn format_age(duration: chrono::Duration) -> String {
+    let secs = duration.num_seconds().max(0) as u64;
+    match secs / 86_400 {
+        0 => format_duration(secs),
+        days => format!("{}d {}h", days, (secs % 86_400) / 3600),
+    }
+}
+
+fn draw_task_flow(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.dim))
+        .title(" Task Flow ");
+
+    let flow = app.task_flow();
+    let show = |duration: Option<chrono::Duration>| {
+        duration.map(format_age).unwrap_or_else(|| "-".to_string())
+    };
+    let row = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{:<14}", label), Style::default().fg(theme.dim)),
+            Span::styled(value, Style::default().fg(theme.text).bold()),
+        ])
+    };
+
+    let lines = vec![
+        row("Done (7 days)", flow.completed_this_week.to_string()),
+        row("Cycle time", show(flow.average_cycle_time)),
+        row("Wait to start", show(flow.average_wait)),
+        row("Open", flow.open.to_string()),
+        row("Oldest open", show(flow.oldest_open)),
+    ];
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Focus time per task list this week
+fn draw_project_breakdown(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.dim))
+        .title(" Lists This Week ");
+
+    let totals = app.dashboard_history().week_focus_by_project();
+    if totals.is_empty() {
+        let empty = Paragraph::new("No focus yet")
+            .style(Style::default().fg(theme.dim))
+            .alignment(Alignment::Center)
+            .block(block);
+        frame.render_widget(empty, area);
+        return;
+    }
+
+    let lines: Vec<Line> = totals
+        .into_iter()
+        .map(|(project, secs)| {
+            let name = project.unwrap_or_else(|| "(no list)".to_string());
+            let name = if name.chars().count() > 13 {
+                format!("{}…", name.chars().take(12).collect::<String>())
+            } else {
+                name
+            };
+            Line::from(vec![
+                Span::styled(format!("{:<14}", name), Style::default().fg(theme.dim)),
+                Span::styled(
+                    format_duration(secs),
+                    Style::default().fg(theme.text).bold(),
+                ),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
 fn draw_stats_cards(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
@@ -96,79 +198,93 @@ fn draw_stats_cards(frame: &mut Frame, area: Rect, app: &App) {
         ])
         .split(area);
 
+    let history = app.dashboard_history();
+
     // Today
-    let today_secs = app.session_history.today_focus_secs();
-    let _today_count = app.session_history.today_session_count();
-    let (completed, goal) = app.daily_goal_progress();
+    let today_secs = history.today_focus_secs();
+    let _today_count = history.today_session_count();
+    let completed = history.today_pomodoro_count();
+    let goal = app.config.daily_goal_pomodoros;
     let goal_status = if completed >= goal as usize { "✓" } else { "" };
     
     let today_block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan))
+        .border_style(Style::default().fg(theme.accent))
         .title(" Today ");
     let today_text = format!("{}\n{}/{} 🎯{}", format_duration(today_secs), completed, goal, goal_status);
     let today = Paragraph::new(today_text)
-        .style(Style::default().fg(Color::White).bold())
+        .style(Style::default().fg(theme.text).bold())
         .alignment(Alignment::Center)
         .block(today_block);
     frame.render_widget(today, chunks[0]);
 
     // This Week
-    let week_secs = app.session_history.week_focus_secs();
+    let week_secs = history.week_focus_secs();
     let week_block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Green))
+        .border_style(Style::default().fg(theme.success))
         .title(" This Week ");
     let week_text = format_duration(week_secs);
     let week = Paragraph::new(week_text)
-        .style(Style::default().fg(Color::White).bold())
+        .style(Style::default().fg(theme.text).bold())
         .alignment(Alignment::Center)
         .block(week_block);
     frame.render_widget(week, chunks[1]);
 
     // Streak
-    let streak_color = if app.session_history.current_streak > 0 {
-        Color::Yellow
+    let streak_color = if history.current_streak() > 0 {
+        theme.highlight
     } else {
-        Color::DarkGray
+        theme.dim
     };
     let streak_block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(streak_color))
         .title(" Streak ");
     let streak_text = format!(
-        "🔥 {} days\n⭐ Best: {}",
-        app.session_history.current_streak,
-        app.session_history.longest_streak
+        "🔥 {} days{}\n⭐ Best: {}",
+        history.current_streak(),
+        if history.is_freeze_day() { " ❄" } else { "" },
+        history.longest_streak()
     );
     let streak = Paragraph::new(streak_text)
-        .style(Style::default().fg(Color::White).bold())
+        .style(Style::default().fg(theme.text).bold())
         .alignment(Alignment::Center)
         .block(streak_block);
     frame.render_widget(streak, chunks[2]);
 
     // All Time
-    let total_secs = app.session_history.total_focus_secs();
-    let total_sessions = app.session_history.sessions.len();
+    let total_secs = history.total_focus_secs();
+    let total_sessions = history.sessions().len();
     let total_block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Magenta))
+        .border_style(Style::default().fg(theme.secondary))
         .title(" All Time ");
-    let total_text = format!("{}\n{} sessions", format_duration(total_secs), total_sessions);
+    let completion = history
+        .completion_rate()
+        .map(|rate| format!(" · {:.0}% done", rate * 100.0))
+        .unwrap_or_default();
+    let total_text = format!(
+        "{}\n{} sessions{}",
+        format_duration(total_secs),
+        total_sessions,
+        completion
+    );
     let total = Paragraph::new(total_text)
-        .style(Style::default().fg(Color::White).bold())
+        .style(Style::default().fg(theme.text).bold())
         .alignment(Alignment::Center)
         .block(total_block);
     frame.render_widget(total, chunks[3]);
 }
 
 fn draw_weekly_chart(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Blue))
+        .border_style(Style::default().fg(theme.info))
         .title(" Weekly Activity ");
 
-    let data = app.session_history.last_7_days_focus();
+    let data = app.dashboard_history().last_7_days_focus();
     let max_mins = data.iter().map(|(_, s)| s / 60).max().unwrap_or(1).max(1);
 
     let bars: Vec<Bar> = data
@@ -178,7 +294,7 @@ fn draw_weekly_chart(frame: &mut Frame, area: Rect, app: &App) {
             Bar::default()
                 .value(mins)
                 .label(Line::from(day.clone()))
-                .style(Style::default().fg(Color::Cyan))
+                .style(Style::default().fg(theme.accent))
         })
         .collect();
 
@@ -187,8 +303,8 @@ fn draw_weekly_chart(frame: &mut Frame, area: Rect, app: &App) {
         .bar_width(5)
         .bar_gap(2)
         .group_gap(0)
-        .bar_style(Style::default().fg(Color::Cyan))
-        .value_style(Style::default().fg(Color::White).bold())
+        .bar_style(Style::default().fg(theme.accent))
+        .value_style(Style::default().fg(theme.text).bold())
         .data(BarGroup::default().bars(&bars))
         .max(max_mins);
 
@@ -196,16 +312,18 @@ fn draw_weekly_chart(frame: &mut Frame, area: Rect, app: &App) {
 }
 
 fn draw_recent_sessions(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::DarkGray))
+        .border_style(Style::default().fg(theme.dim))
         .title(" Recent Sessions ");
 
-    let recent = app.session_history.recent_sessions(10);
+    let history = app.dashboard_history();
+    let recent = history.recent_sessions(10);
 
     if recent.is_empty() {
         let empty = Paragraph::new("No sessions yet. Start a timer!")
-            .style(Style::default().fg(Color::DarkGray))
+            .style(Style::default().fg(theme.dim))
             .alignment(Alignment::Center)
             .block(block);
         frame.render_widget(empty, area);
@@ -213,22 +331,37 @@ fn draw_recent_sessions(frame: &mut Frame, area: Rect, app: &App) {
     }
 
     let header = Row::new(vec!["Time", "Type", "Dur", "Task", "Note"])
-        .style(Style::default().fg(Color::Yellow).bold())
+        .style(Style::default().fg(theme.highlight).bold())
         .bottom_margin(1);
 
     let rows: Vec<Row> = recent
         .iter()
         .map(|s| {
             let time = s.timestamp.format("%m/%d %H:%M").to_string();
-            let session_type = match s.session_type.as_str() {
-                "work" => "🍅",
-                "short_break" => "☕",
-                "long_break" => "🌴",
-                _ => "?",
+            let session_type = match s.session_type {
+                SessionType::Work => "🍅",
+                SessionType::ShortBreak => "☕",
+                SessionType::LongBreak => "🌴",
             };
             let duration = format!("{}m", s.duration_secs / 60);
-            let task = s.task_name.clone().unwrap_or_else(|| "-".to_string());
-            let note = s.note.clone().unwrap_or_else(|| String::new());
+            // Linked tasks show their current name, in case they were renamed
+            let task = s
+                .task_id
+                .and_then(|id| app.tasks.iter().find(|t| t.id == id))
+                .map(|t| t.name.clone())
+                .or_else(|| s.task_name.clone())
+                .unwrap_or_else(|| "-".to_string());
+            // A session split between tasks says how many others shared it
+            let task = match s.shares.len() {
+                0 | 1 => task,
+                n => format!("{} +{}", task, n - 1),
+            };
+            // Cut-short sessions show why instead of an empty note
+            let note = match (&s.note, s.interruption) {
+                (Some(note), _) => note.clone(),
+                (None, Some(reason)) => format!("({})", reason.label()),
+                (None, None) => String::new(),
+            };
             // Truncate note to fit
             let note_display = if note.len() > 20 {
                 format!("{}…", &note[..19])
@@ -236,14 +369,16 @@ fn draw_recent_sessions(frame: &mut Frame, area: Rect, app: &App) {
                 note
             };
 
+            let row_color = if s.completed { theme.text } else { theme.dim };
+
             Row::new(vec![
                 Cell::from(time),
                 Cell::from(session_type),
                 Cell::from(duration),
                 Cell::from(task),
-                Cell::from(Span::styled(note_display, Style::default().fg(Color::DarkGray))),
+                Cell::from(Span::styled(note_display, Style::default().fg(theme.dim))),
             ])
-            .style(Style::default().fg(Color::White))
+            .style(Style::default().fg(row_color))
         })
         .collect();
 
@@ -263,15 +398,36 @@ fn draw_recent_sessions(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(table, area);
 }
 
-fn draw_footer(frame: &mut Frame, area: Rect) {
+fn draw_footer(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let key = |action| format!("[{}]", app.keymap.hint(KeyContext::Dashboard, action));
     let hints = Line::from(vec![
-        Span::styled("[1]", Style::default().fg(Color::Cyan).bold()),
+        Span::styled(
+            key(Action::SwitchView(1)),
+            Style::default().fg(theme.accent).bold(),
+        ),
         Span::raw(" Timer  "),
-        Span::styled("[2]", Style::default().fg(Color::Yellow).bold()),
+        Span::styled(
+            key(Action::SwitchView(2)),
+            Style::default().fg(theme.highlight).bold(),
+        ),
         Span::raw(" Dashboard  "),
-        Span::styled("[3]", Style::default().fg(Color::Magenta).bold()),
+        Span::styled(
+            key(Action::SwitchView(3)),
+            Style::default().fg(theme.secondary).bold(),
+        ),
         Span::raw(" Settings  "),
-        Span::styled("[q]", Style::default().fg(Color::Red).bold()),
+        Span::styled(
+            key(Action::Export),
+            Style::default().fg(theme.success).bold(),
+        ),
+        Span::raw(" Export  "),
+        Span::styled(
+            key(Action::PickProfile),
+            Style::default().fg(theme.info).bold(),
+        ),
+        Span::raw(" Profile  "),
+        Span::styled(key(Action::Quit), Style::default().fg(theme.danger).bold()),
         Span::raw(" Quit"),
     ]);
 
@@ -280,3 +436,87 @@ fn draw_footer(frame: &mut Frame, area: Rect) {
 
     frame.render_widget(footer, area);
 }
+
+/// Draw the export prompt (filter query and format), or the result of the export
+fn draw_export_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = frame.area();
+
+    let popup_width = 64.min(area.width.saturating_sub(4));
+    let popup_height = 8;
+
+    let popup_area = Rect {
+        x: (area.width.saturating_sub(popup_width)) / 2,
+        y: (area.height.saturating_sub(popup_height)) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(border::ROUNDED)
+        .border_style(Style::default().fg(theme.success))
+        .title(" 📤 Export Sessions ")
+        .title_style(Style::default().fg(theme.success).bold());
+
+    let inner_area = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let lines = match &app.export_result {
+        Some(result) => {
+            let (text, color) = match result {
+                Ok(summary) => (format!("✓ {}", summary), theme.success),
+                Err(error) => (format!("✗ {}", error), theme.danger),
+            };
+            vec![
+                Line::from(""),
+                Line::from(Span::styled(text, Style::default().fg(color))),
+                Line::from(""),
+                Line::from(""),
+                Line::from(Span::styled(
+                    "Press any key",
+                    Style::default().fg(theme.dim),
+                )),
+            ]
+        }
+        None => {
+            let formats = [ExportFormat::Csv, ExportFormat::Jsonl, ExportFormat::Ics];
+            let mut format_spans = vec![Span::styled("Format: ", Style::default().fg(theme.dim))];
+            for format in formats {
+                let style = if format == app.export_format {
+                    Style::default().fg(theme.highlight).bold()
+                } else {
+                    Style::default().fg(theme.dim)
+                };
+                format_spans.push(Span::styled(
+                    format!(" {} ", format.extension().to_uppercase()),
+                    style,
+                ));
+            }
+
+            vec![
+                Line::from(Span::styled(
+                    "Filter (from:YYYY-MM-DD to:YYYY-MM-DD #tag task text):",
+                    Style::default().fg(theme.dim),
+                )),
+                Line::from(vec![
+                    Span::styled(app.input_buffer.as_str(), Style::default().fg(theme.text)),
+                    Span::styled("│", Style::default().fg(theme.success)),
+                ]),
+                Line::from(""),
+                Line::from(format_spans),
+                Line::from(Span::styled(
+                    "Tab ▸ format │ Enter ▸ export │ Esc ▸ cancel",
+                    Style::default().fg(theme.dim),
+                )),
+            ]
+        }
+    };
+
+    let content = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    frame.render_widget(content, inner_area);
+}
@@ -1,9 +1,16 @@
 mod dashboard_view;
 mod settings_view;
+pub mod theme;
 mod timer_view;
 
 use crate::app::{App, CurrentView};
-use ratatui::Frame;
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Clear, Paragraph, Wrap},
+    Frame,
+};
 
 /// Main draw function that renders the current view
 pub fn draw(frame: &mut Frame, app: &App) {
@@ -12,4 +19,38 @@ pub fn draw(frame: &mut Frame, app: &App) {
         CurrentView::Dashboard => dashboard_view::draw(frame, app),
         CurrentView::Settings => settings_view::draw(frame, app),
     }
+
+    if !app.storage_errors.is_empty() {
+        draw_error_banner(frame, app);
+    }
+}
+
+/// Storage problems across the top of the screen, over whatever view is showing
+fn draw_error_banner(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = frame.area();
+
+    let mut lines: Vec<Line> = app
+        .storage_errors
+        .iter()
+        .map(|error| Line::from(format!(" ⚠ {}", error)))
+        .collect();
+    lines.push(Line::from(" Press any key to dismiss").fg(theme.dim));
+
+    let width = area.width.max(1) as usize;
+    let height: usize = lines
+        .iter()
+        .map(|line| line.width().div_ceil(width).max(1))
+        .sum();
+    let banner_area = Rect {
+        height: (height as u16).min(area.height),
+        ..area
+    };
+    frame.render_widget(Clear, banner_area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .style(Style::default().fg(theme.text).bg(theme.danger).bold()),
+        banner_area,
+    );
 }
@@ -1,7 +1,8 @@
 use crate::app::{App, InputMode, SettingsCategory, SettingsField};
+use crate::keymap::{Action, KeyContext};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Style, Stylize},
+    style::{Style, Stylize},
     symbols::border,
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph},
@@ -21,9 +22,9 @@ pub fn draw(frame: &mut Frame, app: &App) {
         ])
         .split(area);
 
-    draw_header(frame, chunks[0]);
+    draw_header(frame, chunks[0], app);
     draw_settings_content(frame, chunks[1], app);
-    draw_footer(frame, chunks[2]);
+    draw_footer(frame, chunks[2], app);
 
     // Confirm reset popup
     if app.input_mode == InputMode::ConfirmReset {
@@ -31,7 +32,8 @@ pub fn draw(frame: &mut Frame, app: &App) {
     }
 }
 
-fn draw_header(frame: &mut Frame, area: Rect) {
+fn draw_header(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
@@ -42,27 +44,28 @@ fn draw_header(frame: &mut Frame, area: Rect) {
         .split(area);
 
     let title = Paragraph::new(Line::from(vec![
-        Span::styled("🍅 ", Style::default().fg(Color::Red)),
-        Span::styled("POMO-TUI", Style::default().fg(Color::Cyan).bold()),
+        Span::styled("🍅 ", Style::default().fg(theme.danger)),
+        Span::styled("POMO-TUI", Style::default().fg(theme.accent).bold()),
     ]))
     .alignment(Alignment::Left);
     frame.render_widget(title, chunks[0]);
 
     let mode = Paragraph::new("⚙ Settings")
-        .style(Style::default().fg(Color::Yellow))
+        .style(Style::default().fg(theme.highlight))
         .alignment(Alignment::Center);
     frame.render_widget(mode, chunks[1]);
 
     let help = Paragraph::new("Press 1 for Timer")
-        .style(Style::default().fg(Color::DarkGray))
+        .style(Style::default().fg(theme.dim))
         .alignment(Alignment::Right);
     frame.render_widget(help, chunks[2]);
 }
 
 fn draw_settings_content(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Yellow))
+        .border_style(Style::default().fg(theme.highlight))
         .title(" Settings ");
 
     let inner_area = block.inner(area);
@@ -84,7 +87,7 @@ fn draw_settings_content(frame: &mut Frame, area: Rect, app: &App) {
             }
             lines.push(Line::from(Span::styled(
                 field_category.name(),
-                Style::default().fg(Color::Cyan).bold(),
+                Style::default().fg(theme.accent).bold(),
             )));
             current_category = Some(field_category);
         }
@@ -101,20 +104,26 @@ fn draw_settings_content(frame: &mut Frame, area: Rect, app: &App) {
     lines.push(Line::from(""));
     lines.push(Line::from(Span::styled(
         "STATS (read-only)",
-        Style::default().fg(Color::Cyan).bold(),
+        Style::default().fg(theme.accent).bold(),
     )));
     lines.push(Line::from(vec![
-        Span::styled("  Current Streak", Style::default().fg(Color::Gray)),
+        Span::styled("  Current Streak", Style::default().fg(theme.muted)),
         Span::styled(
-            format!("                  🔥 {} days", app.session_history.current_streak),
-            Style::default().fg(Color::Yellow),
+            format!(
+                "                  🔥 {} days",
+                app.session_history.current_streak()
+            ),
+            Style::default().fg(theme.highlight),
         ),
     ]));
     lines.push(Line::from(vec![
-        Span::styled("  Longest Streak", Style::default().fg(Color::Gray)),
+        Span::styled("  Longest Streak", Style::default().fg(theme.muted)),
         Span::styled(
-            format!("                  ⭐ {} days", app.session_history.longest_streak),
-            Style::default().fg(Color::Magenta),
+            format!(
+                "                  ⭐ {} days",
+                app.session_history.longest_streak()
+            ),
+            Style::default().fg(theme.secondary),
         ),
     ]));
 
@@ -142,20 +151,22 @@ fn draw_settings_content(frame: &mut Frame, area: Rect, app: &App) {
 }
 
 fn make_setting_line(field: &SettingsField, app: &App, is_selected: bool) -> Line<'static> {
+    let theme = &app.theme;
     let pointer = if is_selected { "▸ " } else { "  " };
     let label_style = if is_selected {
-        Style::default().fg(Color::White).bold()
+        Style::default().fg(theme.text).bold()
     } else {
-        Style::default().fg(Color::Gray)
+        Style::default().fg(theme.muted)
     };
 
     let value_style = if is_selected {
-        Style::default().fg(Color::Yellow).bold()
+        Style::default().fg(theme.highlight).bold()
     } else {
-        Style::default().fg(Color::DarkGray)
+        Style::default().fg(theme.dim)
     };
 
     let (label, value) = match field {
+        SettingsField::Profile => ("Profile", app.config.active_profile.clone()),
         SettingsField::WorkDuration => (
             "Work Duration",
             format!("{} min", app.config.work_duration_mins),
@@ -180,6 +191,22 @@ fn make_setting_line(field: &SettingsField, app: &App, is_selected: bool) -> Lin
             "Show Streak",
             if app.config.show_streak { "Yes" } else { "No" }.to_string(),
         ),
+        SettingsField::StreakRule => (
+            "Streak Day",
+            if app.config.streak_needs_goal {
+                "Daily goal".to_string()
+            } else {
+                format!("{}+ pomodoros", app.config.streak_min_pomodoros.max(1))
+            },
+        ),
+        SettingsField::DayStartHour => (
+            "Day Starts At",
+            match &app.config.time_zone {
+                Some(tz) => format!("{:02}:00 ({})", app.config.day_start_hour, tz),
+                None => format!("{:02}:00", app.config.day_start_hour),
+            },
+        ),
+        SettingsField::Theme => ("Theme", app.theme.name.clone()),
         SettingsField::BreathingAnimation => (
             "Breathing Animation",
             if app.config.breathing_enabled { "On" } else { "Off" }.to_string(),
@@ -200,14 +227,29 @@ fn make_setting_line(field: &SettingsField, app: &App, is_selected: bool) -> Lin
             "Focus Mode on Start",
             if app.config.focus_mode_on_start { "Yes" } else { "No" }.to_string(),
         ),
+        SettingsField::TagRetention => (
+            "Forget Unused Tags",
+            match app.config.tag_retention_days {
+                0 => "Never".to_string(),
+                days => format!("After {} days", days),
+            },
+        ),
         SettingsField::NotificationsEnabled => (
             "Desktop Notifications",
             if app.config.notifications_enabled { "Enabled" } else { "Disabled" }.to_string(),
         ),
-        SettingsField::ResetData => (
-            "🗑 Reset All Data",
-            "Press Enter to reset...".to_string(),
+        SettingsField::SoundEnabled => (
+            "Sound on Completion",
+            if !cfg!(feature = "audio") {
+                "Not built in".to_string()
+            } else if app.config.sound_enabled {
+                "Enabled".to_string()
+            } else {
+                "Disabled".to_string()
+            },
         ),
+        SettingsField::SoundVolume => ("Sound Volume", format!("{}%", app.config.sound_volume)),
+        SettingsField::ResetData => ("🗑 Reset All Data", "Press Enter to reset...".to_string()),
     };
 
     let arrows = if is_selected { "◀ " } else { "  " };
@@ -228,17 +270,32 @@ fn make_setting_line(field: &SettingsField, app: &App, is_selected: bool) -> Lin
     ])
 }
 
-fn draw_footer(frame: &mut Frame, area: Rect) {
+fn draw_footer(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let hint = |action| app.keymap.hint(KeyContext::Settings, action);
+    let key = |action| format!("[{}]", hint(action));
     let hints = Line::from(vec![
-        Span::styled("[j/k]", Style::default().fg(Color::Yellow).bold()),
+        Span::styled(
+            format!("[{}/{}]", hint(Action::Down), hint(Action::Up)),
+            Style::default().fg(theme.highlight).bold(),
+        ),
         Span::raw(" Navigate  "),
-        Span::styled("[←/→]", Style::default().fg(Color::Yellow).bold()),
+        Span::styled(
+            format!("[{}/{}]", hint(Action::Decrease), hint(Action::Increase)),
+            Style::default().fg(theme.highlight).bold(),
+        ),
         Span::raw(" Adjust  "),
-        Span::styled("[1]", Style::default().fg(Color::Cyan).bold()),
+        Span::styled(
+            key(Action::SwitchView(1)),
+            Style::default().fg(theme.accent).bold(),
+        ),
         Span::raw(" Timer  "),
-        Span::styled("[2]", Style::default().fg(Color::Cyan).bold()),
+        Span::styled(
+            key(Action::SwitchView(2)),
+            Style::default().fg(theme.accent).bold(),
+        ),
         Span::raw(" Dashboard  "),
-        Span::styled("[q]", Style::default().fg(Color::Red).bold()),
+        Span::styled(key(Action::Quit), Style::default().fg(theme.danger).bold()),
         Span::raw(" Quit"),
     ]);
 
@@ -250,6 +307,7 @@ fn draw_footer(frame: &mut Frame, area: Rect) {
 
 /// Draw confirm reset popup
 fn draw_confirm_reset_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
     let area = frame.area();
 
     let popup_width = 45.min(area.width.saturating_sub(4));
@@ -267,9 +325,9 @@ fn draw_confirm_reset_popup(frame: &mut Frame, app: &App) {
     let block = Block::default()
         .borders(Borders::ALL)
         .border_set(border::DOUBLE)
-        .border_style(Style::default().fg(Color::Red))
+        .border_style(Style::default().fg(theme.danger))
         .title(" ⚠️ RESET ALL DATA ")
-        .title_style(Style::default().fg(Color::Red).bold());
+        .title_style(Style::default().fg(theme.danger).bold());
 
     let inner_area = block.inner(popup_area);
     frame.render_widget(block, popup_area);
@@ -283,16 +341,17 @@ fn draw_confirm_reset_popup(frame: &mut Frame, app: &App) {
         ])
         .split(inner_area);
 
-    let warning = Paragraph::new("This will delete all sessions, tasks,\nand stats. This cannot be undone!")
-        .style(Style::default().fg(Color::Yellow))
-        .alignment(Alignment::Center);
+    let warning =
+        Paragraph::new("This will delete all sessions, tasks,\nand stats. This cannot be undone!")
+            .style(Style::default().fg(theme.highlight))
+            .alignment(Alignment::Center);
     frame.render_widget(warning, chunks[0]);
 
     // Input with feedback
     let input_color = if app.input_buffer == "DELETE" {
-        Color::Green
+        theme.success
     } else {
-        Color::White
+        theme.text
     };
     let input_text = format!("Type DELETE: {}│", app.input_buffer);
     let input = Paragraph::new(input_text)
@@ -301,7 +360,7 @@ fn draw_confirm_reset_popup(frame: &mut Frame, app: &App) {
     frame.render_widget(input, chunks[1]);
 
     let hint = Paragraph::new("Enter ▸ confirm │ Esc ▸ cancel")
-        .style(Style::default().fg(Color::DarkGray))
+        .style(Style::default().fg(theme.dim))
         .alignment(Alignment::Center);
     frame.render_widget(hint, chunks[2]);
 }
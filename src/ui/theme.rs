@@ -0,0 +1,258 @@
+use crate::app::TimerState;
+use anyhow::{Context, Result};
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Names of the themes that ship with the app
+pub const BUILTIN_THEMES: [&str; 5] = ["dark", "light", "high-contrast", "solarized", "nord"];
+
+/// Color palette used by every view
+///
+/// User themes live in `~/.config/pomo-tui/themes/<name>.toml` (or `.json`).
+/// Any color left out falls back to the dark theme.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub name: String,
+
+    // Timer phases
+    pub work: Color,
+    pub short_break: Color,
+    pub long_break: Color,
+
+    // Accents
+    pub accent: Color,    // titles and headings
+    pub secondary: Color, // task pane
+    pub highlight: Color, // selected values, goals, warnings
+    pub success: Color,
+    pub danger: Color,
+    pub info: Color, // tags
+
+    // Text and surfaces
+    pub text: Color,
+    pub muted: Color,
+    pub dim: Color,
+    pub surface: Color, // gauge background
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            name: "dark".to_string(),
+            work: Color::Cyan,
+            short_break: Color::Green,
+            long_break: Color::Magenta,
+            accent: Color::Cyan,
+            secondary: Color::Magenta,
+            highlight: Color::Yellow,
+            success: Color::Green,
+            danger: Color::Red,
+            info: Color::Blue,
+            text: Color::White,
+            muted: Color::Gray,
+            dim: Color::DarkGray,
+            surface: Color::Rgb(40, 40, 40),
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            name: "light".to_string(),
+            work: Color::Rgb(0, 110, 150),
+            short_break: Color::Rgb(30, 130, 50),
+            long_break: Color::Rgb(140, 50, 140),
+            accent: Color::Rgb(0, 110, 150),
+            secondary: Color::Rgb(140, 50, 140),
+            highlight: Color::Rgb(170, 100, 0),
+            success: Color::Rgb(30, 130, 50),
+            danger: Color::Rgb(190, 30, 30),
+            info: Color::Rgb(30, 80, 180),
+            text: Color::Black,
+            muted: Color::Rgb(70, 70, 70),
+            dim: Color::Rgb(140, 140, 140),
+            surface: Color::Rgb(220, 220, 220),
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            name: "high-contrast".to_string(),
+            work: Color::LightCyan,
+            short_break: Color::LightGreen,
+            long_break: Color::LightMagenta,
+            accent: Color::LightCyan,
+            secondary: Color::LightMagenta,
+            highlight: Color::LightYellow,
+            success: Color::LightGreen,
+            danger: Color::LightRed,
+            info: Color::LightBlue,
+            text: Color::White,
+            muted: Color::White,
+            dim: Color::Gray,
+            surface: Color::Rgb(60, 60, 60),
+        }
+    }
+
+    pub fn solarized() -> Self {
+        Self {
+            name: "solarized".to_string(),
+            work: Color::Rgb(38, 139, 210),
+            short_break: Color::Rgb(133, 153, 0),
+            long_break: Color::Rgb(108, 113, 196),
+            accent: Color::Rgb(42, 161, 152),
+            secondary: Color::Rgb(211, 54, 130),
+            highlight: Color::Rgb(181, 137, 0),
+            success: Color::Rgb(133, 153, 0),
+            danger: Color::Rgb(220, 50, 47),
+            info: Color::Rgb(38, 139, 210),
+            text: Color::Rgb(238, 232, 213),
+            muted: Color::Rgb(147, 161, 161),
+            dim: Color::Rgb(88, 110, 117),
+            surface: Color::Rgb(7, 54, 66),
+        }
+    }
+
+    pub fn nord() -> Self {
+        Self {
+            name: "nord".to_string(),
+            work: Color::Rgb(136, 192, 208),
+            short_break: Color::Rgb(163, 190, 140),
+            long_break: Color::Rgb(180, 142, 173),
+            accent: Color::Rgb(143, 188, 187),
+            secondary: Color::Rgb(180, 142, 173),
+            highlight: Color::Rgb(235, 203, 139),
+            success: Color::Rgb(163, 190, 140),
+            danger: Color::Rgb(191, 97, 106),
+            info: Color::Rgb(129, 161, 193),
+            text: Color::Rgb(236, 239, 244),
+            muted: Color::Rgb(216, 222, 233),
+            dim: Color::Rgb(97, 110, 136),
+            surface: Color::Rgb(59, 66, 82),
+        }
+    }
+
+    fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "solarized" => Some(Self::solarized()),
+            "nord" => Some(Self::nord()),
+            _ => None,
+        }
+    }
+
+    fn themes_dir() -> Result<PathBuf> {
+        Ok(crate::persistence::config_dir()?.join("themes"))
+    }
+
+    /// Load a theme by name: built-in first, then user files, then dark
+    ///
+    /// A user theme file that can't be read is an error, so it isn't mistaken for the dark theme.
+    pub fn load(name: &str) -> Result<Self> {
+        match Self::builtin(name) {
+            Some(theme) => Ok(theme),
+            None => Ok(Self::load_user(name)?.unwrap_or_default()),
+        }
+    }
+
+    fn load_user(name: &str) -> Result<Option<Self>> {
+        let dir = Self::themes_dir()?;
+
+        let toml_path = dir.join(format!("{}.toml", name));
+        let json_path = dir.join(format!("{}.json", name));
+        let mut theme: Theme = if toml_path.exists() {
+            let contents = fs::read_to_string(&toml_path)?;
+            toml::from_str(&contents)
+                .with_context(|| format!("theme {} is damaged", toml_path.display()))?
+        } else if json_path.exists() {
+            let contents = fs::read_to_string(&json_path)?;
+            serde_json::from_str(&contents)
+                .with_context(|| format!("theme {} is damaged", json_path.display()))?
+        } else {
+            return Ok(None);
+        };
+
+        theme.name = name.to_string();
+        Ok(Some(theme))
+    }
+
+    /// Built-in theme names followed by any user themes on disk
+    pub fn available() -> Vec<String> {
+        let mut names: Vec<String> = BUILTIN_THEMES.iter().map(|n| n.to_string()).collect();
+
+        let mut user: Vec<String> = Self::themes_dir()
+            .and_then(|dir| Ok(fs::read_dir(dir)?))
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok().map(|e| e.path()))
+                    .filter(|p| {
+                        matches!(
+                            p.extension().and_then(|e| e.to_str()),
+                            Some("toml" | "json")
+                        )
+                    })
+                    .filter_map(|p| p.file_stem().and_then(|s| s.to_str()).map(str::to_string))
+                    .filter(|n| !names.contains(n))
+                    .collect()
+            })
+            .unwrap_or_default();
+        user.sort();
+        user.dedup();
+
+        names.extend(user);
+        names
+    }
+
+    /// Color for a timer phase
+    pub fn state_color(&self, state: TimerState) -> Color {
+        match state {
+            TimerState::Work => self.work,
+            TimerState::ShortBreak => self.short_break,
+            TimerState::LongBreak => self.long_break,
+        }
+    }
+}
+
+/// `from` moved `amount` (0.0 to 1.0) of the way towards `to`
+///
+/// Named colors are mixed as their usual xterm values; indexed and reset colors are left alone.
+pub fn blend(from: Color, to: Color, amount: f32) -> Color {
+    let (Some((r1, g1, b1)), Some((r2, g2, b2))) = (rgb(from), rgb(to)) else {
+        return from;
+    };
+    let mix =
+        |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * amount.clamp(0.0, 1.0)).round() as u8;
+    Color::Rgb(mix(r1, r2), mix(g1, g2), mix(b1, b2))
+}
+
+fn rgb(color: Color) -> Option<(u8, u8, u8)> {
+    Some(match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Black => (0, 0, 0),
+        Color::Red => (205, 0, 0),
+        Color::Green => (0, 205, 0),
+        Color::Yellow => (205, 205, 0),
+        Color::Blue => (0, 0, 238),
+        Color::Magenta => (205, 0, 205),
+        Color::Cyan => (0, 205, 205),
+        Color::Gray => (229, 229, 229),
+        Color::DarkGray => (127, 127, 127),
+        Color::LightRed => (255, 0, 0),
+        Color::LightGreen => (0, 255, 0),
+        Color::LightYellow => (255, 255, 0),
+        Color::LightBlue => (92, 92, 255),
+        Color::LightMagenta => (255, 0, 255),
+        Color::LightCyan => (0, 255, 255),
+        Color::White => (255, 255, 255),
+        Color::Reset | Color::Indexed(_) => return None,
+    })
+}
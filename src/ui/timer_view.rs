@@ -1,4 +1,7 @@
-use crate::app::{ActivePane, App, InputMode};
+use crate::app::{format_duration_input, parse_duration, ActivePane, App, InputMode, TimerMode};
+use crate::keymap::{Action, KeyContext};
+use crate::persistence::tasks::{Priority, Subtask, TaskSort};
+use crate::ui::theme::{self, Theme};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style, Stylize},
@@ -24,18 +27,51 @@ pub fn draw(frame: &mut Frame, app: &App) {
         draw_input_popup(frame, app, "Add Task");
     } else if app.input_mode == InputMode::EditingTask {
         draw_input_popup(frame, app, "Edit Task");
+    } else if app.input_mode == InputMode::AddingSubtask {
+        draw_input_popup(frame, app, "Add Subtask");
+    } else if app.input_mode == InputMode::EditingSubtask {
+        draw_input_popup(frame, app, "Edit Subtask");
     } else if app.input_mode == InputMode::QuickCapture {
         draw_input_popup(frame, app, "Quick Capture");
     }
     // Session note popup removed for inline flow (Steve Jobs polish)
 
+    if app.input_mode == InputMode::ConfirmResume {
+        draw_resume_popup(frame, app);
+    }
+
+    if app.input_mode == InputMode::SettingDuration {
+        draw_duration_popup(frame, app);
+    }
+
+    if app.input_mode == InputMode::PickingProfile {
+        draw_profile_popup(frame, app);
+    }
+
+    if app.input_mode == InputMode::BrowsingArchive {
+        draw_archive_popup(frame, app);
+    }
+
+    if app.input_mode == InputMode::ManagingTags {
+        draw_tag_popup(frame, app);
+    } else if app.input_mode == InputMode::RenamingTag {
+        draw_tag_popup(frame, app);
+        draw_input_popup(frame, app, "Rename Tag");
+    }
+
+    if app.input_mode == InputMode::PickingProject {
+        draw_project_popup(frame, app);
+    } else if app.input_mode == InputMode::NamingProject {
+        draw_input_popup(frame, app, "New List");
+    }
+
     // Celebration overlay (top priority)
     if app.show_celebration {
         draw_celebration_overlay(frame, app);
     }
 
     if app.show_help {
-        draw_help_overlay(frame);
+        draw_help_overlay(frame, app);
     }
 }
 
@@ -60,6 +96,7 @@ fn draw_normal_mode(frame: &mut Frame, area: Rect, app: &App) {
 
 /// Draw focus mode - full screen timer
 fn draw_focus_mode(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
     let state_color = get_breathing_color(app);
 
     // Minimal header
@@ -75,9 +112,9 @@ fn draw_focus_mode(frame: &mut Frame, area: Rect, app: &App) {
     // Minimal header with exit hint
     let header = Paragraph::new(Line::from(vec![
         Span::styled("Focus Mode", Style::default().fg(state_color).bold()),
-        Span::styled(" │ ", Style::default().fg(Color::DarkGray)),
-        Span::styled("Esc", Style::default().fg(Color::DarkGray)),
-        Span::styled(" to exit", Style::default().fg(Color::DarkGray)),
+        Span::styled(" │ ", Style::default().fg(theme.dim)),
+        Span::styled("Esc", Style::default().fg(theme.dim)),
+        Span::styled(" to exit", Style::default().fg(theme.dim)),
     ]))
     .alignment(Alignment::Center);
     frame.render_widget(header, chunks[0]);
@@ -91,6 +128,7 @@ fn draw_focus_mode(frame: &mut Frame, area: Rect, app: &App) {
 
 /// Draw the big timer in focus mode
 fn draw_focus_timer(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
     let state_color = get_breathing_color(app);
 
     let inner = Layout::default()
@@ -119,7 +157,11 @@ fn draw_focus_timer(frame: &mut Frame, area: Rect, app: &App) {
     } else {
         ("RUNNING", "▶")
     };
-    let status_color = if app.is_paused { Color::Yellow } else { Color::Green };
+    let status_color = if app.is_paused {
+        theme.highlight
+    } else {
+        theme.success
+    };
     let status = Paragraph::new(format!("{} {}", status_icon, status_text))
         .style(Style::default().fg(status_color).bold())
         .alignment(Alignment::Center);
@@ -135,31 +177,15 @@ fn draw_focus_timer(frame: &mut Frame, area: Rect, app: &App) {
     }
 }
 
-/// Get color with breathing effect applied
+/// Get color with breathing effect applied: while paused the phase color fades towards the
+/// theme's surface color and back
 fn get_breathing_color(app: &App) -> Color {
-    let base_color = app.timer_state.color();
-    
+    let base_color = app.theme.state_color(app.timer_state);
+
     if app.config.breathing_enabled && app.is_paused {
-        let intensity = app.breathing_intensity();
-        // Modulate brightness
-        match base_color {
-            Color::Cyan => Color::Rgb(
-                (0.0 + 100.0 * intensity) as u8,
-                (139.0 + 80.0 * intensity) as u8,
-                (139.0 + 80.0 * intensity) as u8,
-            ),
-            Color::Green => Color::Rgb(
-                (0.0 + 50.0 * intensity) as u8,
-                (128.0 + 100.0 * intensity) as u8,
-                (0.0 + 50.0 * intensity) as u8,
-            ),
-            Color::Magenta => Color::Rgb(
-                (139.0 + 80.0 * intensity) as u8,
-                (0.0 + 50.0 * intensity) as u8,
-                (139.0 + 80.0 * intensity) as u8,
-            ),
-            _ => base_color,
-        }
+        // Intensity runs 0.5..=1.0, so the color fades at most 40% of the way
+        let fade = 0.8 * (1.0 - app.breathing_intensity());
+        theme::blend(base_color, app.theme.surface, fade)
     } else {
         base_color
     }
@@ -183,6 +209,7 @@ fn get_session_dots(app: &App) -> String {
 
 /// Draw daily goal progress bar
 fn draw_daily_goal_bar(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
     let (completed, goal) = app.daily_goal_progress();
     let progress = (completed as f64 / goal as f64).min(1.0);
     
@@ -193,9 +220,9 @@ fn draw_daily_goal_bar(frame: &mut Frame, area: Rect, app: &App) {
     };
     
     let color = if completed >= goal as usize {
-        Color::Green
+        theme.success
     } else {
-        Color::Yellow
+        theme.highlight
     };
 
     let chunks = Layout::default()
@@ -208,7 +235,7 @@ fn draw_daily_goal_bar(frame: &mut Frame, area: Rect, app: &App) {
         .split(area);
 
     let gauge = Gauge::default()
-        .gauge_style(Style::default().fg(color).bg(Color::Rgb(40, 40, 40)))
+        .gauge_style(Style::default().fg(color).bg(theme.surface))
         .ratio(progress)
         .label(goal_text);
     
@@ -217,6 +244,7 @@ fn draw_daily_goal_bar(frame: &mut Frame, area: Rect, app: &App) {
 
 /// Draw the header with title, mode indicator, and help hint
 fn draw_header(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
@@ -227,25 +255,25 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App) {
         .split(area);
 
     // Left: Greeting with streak
-    let streak_display = if app.config.show_streak && app.session_history.current_streak > 0 {
-        format!(" 🔥{}", app.session_history.current_streak)
+    let streak_display = if app.config.show_streak && app.session_history.current_streak() > 0 {
+        format!(" 🔥{}", app.session_history.current_streak())
     } else {
         String::new()
     };
     
     let greeting = app.greeting();
     let title = Paragraph::new(Line::from(vec![
-        Span::styled(format!("{} ", greeting), Style::default().fg(Color::DarkGray)),
-        Span::styled("│ ", Style::default().fg(Color::DarkGray)),
-        Span::styled("🍅", Style::default().fg(Color::Red)),
-        Span::styled(streak_display, Style::default().fg(Color::Yellow)),
+        Span::styled(format!("{} ", greeting), Style::default().fg(theme.dim)),
+        Span::styled("│ ", Style::default().fg(theme.dim)),
+        Span::styled("🍅", Style::default().fg(theme.danger)),
+        Span::styled(streak_display, Style::default().fg(theme.highlight)),
     ]))
     .alignment(Alignment::Left);
     frame.render_widget(title, chunks[0]);
 
     // Center: Mode + estimated end time (when running)
     let (completed, goal) = app.daily_goal_progress();
-    let end_time_display = if !app.is_paused {
+    let end_time_display = if !app.is_paused && !app.is_counting_up() {
         format!(" → {}", app.estimated_end_time())
     } else {
         String::new()
@@ -254,7 +282,7 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App) {
     
     let mode = Paragraph::new(Line::from(vec![
         Span::styled(app.mode_display(), Style::default().fg(mode_color)),
-        Span::styled(end_time_display, Style::default().fg(Color::DarkGray)),
+        Span::styled(end_time_display, Style::default().fg(theme.dim)),
     ]))
     .alignment(Alignment::Center);
     frame.render_widget(mode, chunks[1]);
@@ -262,13 +290,16 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App) {
     // Right: Goal progress or late night warning
     let right_content = if app.is_late_night() {
         Line::from(vec![
-            Span::styled("🌙 ", Style::default().fg(Color::Yellow)),
-            Span::styled("It's late!", Style::default().fg(Color::Yellow)),
+            Span::styled("🌙 ", Style::default().fg(theme.highlight)),
+            Span::styled("It's late!", Style::default().fg(theme.highlight)),
         ])
     } else {
         Line::from(vec![
-            Span::styled(format!("{}/{} ", completed, goal), Style::default().fg(Color::DarkGray)),
-            Span::styled("🎯", Style::default().fg(Color::Yellow)),
+            Span::styled(
+                format!("{}/{} ", completed, goal),
+                Style::default().fg(theme.dim),
+            ),
+            Span::styled("🎯", Style::default().fg(theme.highlight)),
         ])
     };
     let help = Paragraph::new(right_content)
@@ -289,10 +320,11 @@ fn draw_main_content(frame: &mut Frame, area: Rect, app: &App) {
 
 /// Draw the timer pane with countdown display
 fn draw_timer_pane(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
     let state_color = get_breathing_color(app);
     let is_focused = app.active_pane == ActivePane::Timer;
 
-    let border_color = if is_focused { state_color } else { Color::DarkGray };
+    let border_color = if is_focused { state_color } else { theme.dim };
     let title = format!(" {} Timer ", get_state_icon(app));
 
     let block = Block::default()
@@ -345,6 +377,7 @@ fn draw_timer_pane(frame: &mut Frame, area: Rect, app: &App) {
     // Status OR Inline Note
     if app.input_mode == InputMode::SessionNote {
         draw_inline_note_input(frame, timer_chunks[4], app);
+        draw_session_credit(frame, timer_chunks[5], app);
     } else {
         draw_status(frame, timer_chunks[4], app);
     }
@@ -354,6 +387,7 @@ fn draw_timer_pane(frame: &mut Frame, area: Rect, app: &App) {
 }
 
 fn draw_inline_note_input(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
     let input_text = if app.input_buffer.is_empty() {
         "What did you achieve? (Type, or Space to skip)"
     } else {
@@ -361,19 +395,14 @@ fn draw_inline_note_input(frame: &mut Frame, area: Rect, app: &App) {
     };
     
     let text_color = if app.input_buffer.is_empty() {
-        Color::DarkGray
+        theme.dim
     } else {
-        Color::Yellow
+        theme.highlight
     };
 
     let cursor = if app.celebration_timer % 10 < 5 { "█" } else { " " }; // Pulsing cursor using existing timer
     
-    // Only show cursor if buffer is not empty or we are focused
-    let display_text = if app.input_buffer.is_empty() {
-         format!("{} {}", input_text, cursor)
-    } else {
-         format!("{} {}", input_text, cursor)
-    };
+    let display_text = format!("{} {}", input_text, cursor);
 
     let p = Paragraph::new(display_text)
         .style(Style::default().fg(text_color))
@@ -384,6 +413,48 @@ fn draw_inline_note_input(frame: &mut Frame, area: Rect, app: &App) {
 
 
 
+/// Which task a session split between several gets credited with (Tab reassigns it)
+fn draw_session_credit(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let Some(pending) = app.pending_session.as_ref().filter(|p| p.shares.len() > 1) else {
+        return;
+    };
+
+    let mut spans = vec![Span::styled("Credit: ", Style::default().fg(theme.dim))];
+    for (i, share) in pending.shares.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled(" · ", Style::default().fg(theme.dim)));
+        }
+        let style = if pending.credited == Some(i) {
+            Style::default().fg(theme.highlight).bold()
+        } else {
+            Style::default().fg(theme.muted)
+        };
+        let marker = match (pending.credited == Some(i), pending.earns_pomodoro) {
+            (true, true) => "🍅 ",
+            (true, false) => "▸ ",
+            (false, _) => "",
+        };
+        spans.push(Span::styled(
+            format!("{}{} {}m", marker, share.task_name, share.secs / 60),
+            style,
+        ));
+    }
+    if pending.credited.is_none() {
+        spans.push(Span::styled(
+            " · no task",
+            Style::default().fg(theme.highlight).bold(),
+        ));
+    }
+    spans.push(Span::styled(
+        "  [Tab] reassign",
+        Style::default().fg(theme.dim),
+    ));
+
+    let line = Paragraph::new(Line::from(spans)).alignment(Alignment::Center);
+    frame.render_widget(line, Rect { height: 1, ..area });
+}
+
 fn get_state_icon(app: &App) -> &'static str {
     match app.timer_state {
         crate::app::TimerState::Work => "🍅",
@@ -407,6 +478,7 @@ fn draw_big_timer(frame: &mut Frame, area: Rect, app: &App) {
 }
 
 fn draw_enhanced_progress(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
     let state_color = get_breathing_color(app);
     let progress = app.progress();
     let percentage = (progress * 100.0) as u16;
@@ -436,17 +508,18 @@ fn draw_enhanced_progress(frame: &mut Frame, area: Rect, app: &App) {
         .split(chunks[2])[1];
 
     let gauge = Gauge::default()
-        .gauge_style(Style::default().fg(state_color).bg(Color::Rgb(40, 40, 40)))
+        .gauge_style(Style::default().fg(state_color).bg(theme.surface))
         .ratio(progress)
         .label("");
     frame.render_widget(gauge, gauge_area);
 }
 
 fn draw_status(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
     let (status_text, status_color, status_icon) = if app.is_paused {
-        ("PAUSED", Color::Yellow, "⏸")
+        ("PAUSED", theme.highlight, "⏸")
     } else {
-        ("RUNNING", Color::Green, "▶")
+        ("RUNNING", theme.success, "▶")
     };
 
     let status_line = Line::from(vec![
@@ -460,26 +533,52 @@ fn draw_status(frame: &mut Frame, area: Rect, app: &App) {
 }
 
 fn draw_session_info(frame: &mut Frame, area: Rect, app: &App) {
-    if !matches!(app.timer_mode, crate::app::TimerMode::Pomodoro) {
+    let theme = &app.theme;
+    if let crate::app::TimerMode::Timer(secs) = app.timer_mode {
         let timer_info = Line::from(vec![
-            Span::styled("⏱ ", Style::default().fg(Color::Blue)),
-            Span::styled("Timer Mode", Style::default().fg(Color::Gray)),
-            Span::styled(" ⏱", Style::default().fg(Color::Blue)),
+            Span::styled("⏱ ", Style::default().fg(theme.info)),
+            Span::styled(
+                format!("Timer Mode · {}", format_duration_input(secs)),
+                Style::default().fg(theme.muted),
+            ),
+            Span::styled(" ⏱", Style::default().fg(theme.info)),
         ]);
         let widget = Paragraph::new(timer_info).alignment(Alignment::Center);
         frame.render_widget(widget, area);
         return;
     }
 
+    if app.timer_mode == TimerMode::Stopwatch {
+        let info = if app.is_counting_up() {
+            format!(
+                "Flow · break = 1/{} of focus",
+                app.config.flow_break_divisor.max(1)
+            )
+        } else {
+            format!(
+                "Suggested break · {}",
+                format_duration_input(app.flow_break.as_secs())
+            )
+        };
+        let flow_info = Line::from(vec![
+            Span::styled("◎ ", Style::default().fg(theme.info)),
+            Span::styled(info, Style::default().fg(theme.muted)),
+            Span::styled(" ◎", Style::default().fg(theme.info)),
+        ]);
+        let widget = Paragraph::new(flow_info).alignment(Alignment::Center);
+        frame.render_widget(widget, area);
+        return;
+    }
+
     let session_dots = get_session_dots(app);
     let state_color = get_breathing_color(app);
 
     let session_line = Line::from(vec![
-        Span::styled("Sessions: ", Style::default().fg(Color::DarkGray)),
+        Span::styled("Sessions: ", Style::default().fg(theme.dim)),
         Span::styled(session_dots, Style::default().fg(state_color)),
         Span::styled(
             format!(" │ {}", app.timer_state.display_name()),
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.dim),
         ),
     ]);
 
@@ -489,79 +588,167 @@ fn draw_session_info(frame: &mut Frame, area: Rect, app: &App) {
 
 /// Draw the task pane with task list
 fn draw_task_pane(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
     let is_focused = app.active_pane == ActivePane::Tasks;
-    let border_color = if is_focused { Color::Magenta } else { Color::DarkGray };
+    let border_color = if is_focused {
+        theme.secondary
+    } else {
+        theme.dim
+    };
 
-    let task_count = app.tasks.len();
-    let completed_count = app.tasks.iter().filter(|t| t.completed).count();
+    let visible = app.visible_tasks();
+    let task_count = visible.len();
+    let completed_count = visible.iter().filter(|&&i| app.tasks[i].completed).count();
 
-    let title = if is_focused {
-        format!(" 📋 Tasks ({}/{}) ", completed_count, task_count)
+    // Name the list once there is more than one
+    let list_name = if app.projects.len() > 1 {
+        app.active_project.as_str()
+    } else {
+        "Tasks"
+    };
+    let mut title = if is_focused {
+        format!(" 📋 {} ({}/{})", list_name, completed_count, task_count)
     } else {
-        format!(" Tasks ({}/{}) ", completed_count, task_count)
+        format!(" {} ({}/{})", list_name, completed_count, task_count)
     };
+    if app.task_sort != TaskSort::Manual {
+        title.push_str(&format!(" ↕{}", app.task_sort.name()));
+    }
+    title.push(' ');
 
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(border_color))
         .title(title)
-        .title_style(Style::default().fg(Color::Magenta).bold());
+        .title_style(Style::default().fg(theme.secondary).bold());
 
-    let inner_area = block.inner(area);
+    let mut inner_area = block.inner(area);
     frame.render_widget(block, area);
 
-    if app.tasks.is_empty() {
+    // The search line sits above the list while searching or filtered
+    let searching = app.input_mode == InputMode::SearchingTasks;
+    if searching || !app.task_query.is_empty() {
+        let mut spans = vec![
+            Span::styled("🔍 ", Style::default().fg(theme.info)),
+            Span::styled(&app.task_query, Style::default().fg(theme.text)),
+        ];
+        if searching {
+            spans.push(Span::styled("▏", Style::default().fg(theme.highlight)));
+            if app.task_query.is_empty() {
+                spans.push(Span::styled(
+                    "name #tag is:open is:done",
+                    Style::default()
+                        .fg(theme.dim)
+                        .add_modifier(Modifier::ITALIC),
+                ));
+            }
+        }
+        let search_area = Rect {
+            height: 1,
+            ..inner_area
+        };
+        frame.render_widget(Paragraph::new(Line::from(spans)), search_area);
+        inner_area.y += 1;
+        inner_area.height = inner_area.height.saturating_sub(1);
+
+        if task_count == 0 {
+            let empty = Paragraph::new(Span::styled(
+                "No matching tasks",
+                Style::default().fg(theme.dim),
+            ))
+            .alignment(Alignment::Center);
+            frame.render_widget(empty, inner_area);
+            return;
+        }
+    }
+
+    if task_count == 0 {
         let empty_lines = vec![
             Line::from(""),
-            Line::from(Span::styled("No tasks yet", Style::default().fg(Color::DarkGray))),
+            Line::from(Span::styled("No tasks yet", Style::default().fg(theme.dim))),
             Line::from(""),
-            Line::from(Span::styled("Press 'a' or '/' to add", Style::default().fg(Color::Yellow))),
+            Line::from(Span::styled(
+                format!(
+                    "Press '{}' or '{}' to add",
+                    app.keymap.hint(KeyContext::Timer, Action::AddTask),
+                    app.keymap.hint(KeyContext::Timer, Action::QuickCapture)
+                ),
+                Style::default().fg(theme.highlight),
+            )),
         ];
         let empty_msg = Paragraph::new(empty_lines).alignment(Alignment::Center);
         frame.render_widget(empty_msg, inner_area);
         return;
     }
 
-    let items: Vec<ListItem> = app
-        .tasks
-        .iter()
-        .enumerate()
-        .map(|(i, task)| {
-            let is_selected = i == app.selected_task_index;
+    let today = chrono::Local::now().date_naive();
+    let items: Vec<ListItem> = visible
+        .into_iter()
+        .map(|i| {
+            let task = &app.tasks[i];
+            let is_selected = i == app.selected_task_index && app.selected_subtask.is_none();
 
             let checkbox = if task.completed { "✓" } else { "○" };
-            let checkbox_color = if task.completed { Color::Green } else { Color::DarkGray };
+            let checkbox_color = if task.completed {
+                theme.success
+            } else {
+                theme.dim
+            };
             let pointer = if is_selected { "▸" } else { " " };
-            let pointer_color = if is_selected { Color::Magenta } else { Color::DarkGray };
-
-            let pomodoro_display = if task.pomodoros_spent > 0 {
-                format!(" 🍅×{}", task.pomodoros_spent)
+            let pointer_color = if is_selected {
+                theme.secondary
             } else {
-                String::new()
+                theme.dim
             };
 
+            let pomodoro_display = task
+                .pomodoro_label()
+                .map(|label| format!(" {}", label))
+                .unwrap_or_default();
+            let priority_color = match task.priority {
+                Priority::Urgent => theme.danger,
+                Priority::High => theme.highlight,
+                _ => theme.dim,
+            };
+            let due_display = task.due.map(|due| {
+                let color = if task.is_overdue(today) {
+                    theme.danger
+                } else if due == today {
+                    theme.highlight
+                } else {
+                    theme.dim
+                };
+                Span::styled(
+                    format!(" 📅{}", due.format("%m-%d")),
+                    Style::default().fg(color),
+                )
+            });
+
             // Build tag display
             let tags_display: Vec<Span> = task
                 .tags
                 .iter()
                 .map(|tag| {
-                    Span::styled(
-                        format!(" #{}", tag),
-                        Style::default().fg(Color::Blue).add_modifier(Modifier::DIM),
-                    )
+                    let style = match tag_color(app, tag) {
+                        Some(color) => Style::default().fg(color),
+                        None => Style::default().fg(theme.info).add_modifier(Modifier::DIM),
+                    };
+                    Span::styled(format!(" #{}", tag), style)
                 })
                 .collect();
 
             let name_style = if is_selected {
                 if task.completed {
-                    Style::default().fg(Color::Green).bold()
+                    Style::default().fg(theme.success).bold()
                 } else {
-                    Style::default().fg(Color::White).bold()
+                    Style::default().fg(theme.text).bold()
                 }
             } else if task.completed {
-                Style::default().fg(Color::DarkGray).add_modifier(Modifier::CROSSED_OUT)
+                Style::default()
+                    .fg(theme.dim)
+                    .add_modifier(Modifier::CROSSED_OUT)
             } else {
-                Style::default().fg(Color::Gray)
+                Style::default().fg(theme.muted)
             };
 
             // Truncate long task names
@@ -574,12 +761,48 @@ fn draw_task_pane(frame: &mut Frame, area: Rect, app: &App) {
             let mut spans = vec![
                 Span::styled(format!("{} ", pointer), Style::default().fg(pointer_color)),
                 Span::styled(format!("{} ", checkbox), Style::default().fg(checkbox_color)),
-                Span::styled(display_name, name_style),
-                Span::styled(pomodoro_display, Style::default().fg(Color::Red)),
             ];
+            if !task.priority.marker().is_empty() {
+                spans.push(Span::styled(
+                    format!("{} ", task.priority.marker()),
+                    Style::default().fg(priority_color).bold(),
+                ));
+            }
+            spans.push(Span::styled(display_name, name_style));
+            if let Some((done, total)) = task.checklist_progress() {
+                let fold = if task.expanded { "▾" } else { "▸" };
+                spans.push(Span::styled(
+                    format!(" {}{}/{}", fold, done, total),
+                    Style::default().fg(theme.dim),
+                ));
+            }
+            spans.push(Span::styled(
+                pomodoro_display,
+                Style::default().fg(theme.danger),
+            ));
+            spans.extend(due_display);
             spans.extend(tags_display);
 
-            ListItem::new(Line::from(spans))
+            // The selected task shows its description underneath
+            let mut lines = vec![Line::from(spans)];
+            if let (true, Some(description)) = (is_selected, &task.description) {
+                lines.push(Line::from(Span::styled(
+                    format!("    ↳ {}", description),
+                    Style::default()
+                        .fg(theme.dim)
+                        .add_modifier(Modifier::ITALIC),
+                )));
+            }
+
+            if task.expanded {
+                for (s, subtask) in task.subtasks.iter().enumerate() {
+                    let is_selected =
+                        i == app.selected_task_index && app.selected_subtask == Some(s);
+                    lines.push(subtask_line(subtask, is_selected, theme));
+                }
+            }
+
+            ListItem::new(lines)
         })
         .collect();
 
@@ -587,8 +810,47 @@ fn draw_task_pane(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(list, inner_area);
 }
 
+/// One indented checklist row under an expanded task
+fn subtask_line<'a>(subtask: &'a Subtask, is_selected: bool, theme: &Theme) -> Line<'a> {
+    let pointer = if is_selected { "▸" } else { " " };
+    let (checkbox, checkbox_color) = if subtask.completed {
+        ("✓", theme.success)
+    } else {
+        ("○", theme.dim)
+    };
+    let name_style = if is_selected {
+        Style::default().fg(theme.text).bold()
+    } else if subtask.completed {
+        Style::default()
+            .fg(theme.dim)
+            .add_modifier(Modifier::CROSSED_OUT)
+    } else {
+        Style::default().fg(theme.muted)
+    };
+
+    let mut spans = vec![
+        Span::styled(
+            format!("{}   ", pointer),
+            Style::default().fg(theme.secondary),
+        ),
+        Span::styled(
+            format!("{} ", checkbox),
+            Style::default().fg(checkbox_color),
+        ),
+        Span::styled(subtask.name.as_str(), name_style),
+    ];
+    if subtask.pomodoros_spent > 0 {
+        spans.push(Span::styled(
+            format!(" 🍅×{}", subtask.pomodoros_spent),
+            Style::default().fg(theme.danger),
+        ));
+    }
+    Line::from(spans)
+}
+
 /// Draw input popup for adding a new task
 fn draw_input_popup(frame: &mut Frame, app: &App, title: &str) {
+    let theme = &app.theme;
     let area = frame.area();
 
     let popup_width = 58.min(area.width.saturating_sub(4));
@@ -605,9 +867,9 @@ fn draw_input_popup(frame: &mut Frame, app: &App, title: &str) {
 
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Yellow))
+        .border_style(Style::default().fg(theme.highlight))
         .title(format!(" ✏️ {} ", title))
-        .title_style(Style::default().fg(Color::Yellow).bold());
+        .title_style(Style::default().fg(theme.highlight).bold());
 
     let inner_area = block.inner(popup_area);
     frame.render_widget(block, popup_area);
@@ -623,14 +885,20 @@ fn draw_input_popup(frame: &mut Frame, app: &App, title: &str) {
         .split(inner_area);
 
     // Prompt
-    let prompt = Paragraph::new("Task name (use #tag for tags):")
-        .style(Style::default().fg(Color::DarkGray));
+    let prompt_text = match app.input_mode {
+        InputMode::AddingSubtask | InputMode::EditingSubtask => "Subtask name:",
+        InputMode::NamingProject => "List name:",
+        InputMode::RenamingTag => "New name (an existing tag merges into it):",
+        _ => "Task (#tag !high ~4 @2026-10-20 -- notes):",
+    };
+    let prompt = Paragraph::new(prompt_text).style(Style::default().fg(theme.dim));
     frame.render_widget(prompt, chunks[0]);
 
     // Input with ghost text suggestion
-    let mut input_spans = vec![
-        Span::styled(&app.input_buffer, Style::default().fg(Color::White)),
-    ];
+    let mut input_spans = vec![Span::styled(
+        &app.input_buffer,
+        Style::default().fg(theme.text),
+    )];
 
     // Show ghost text if there's a suggestion
     if let Some(ref suggestion) = app.tag_suggestion {
@@ -641,30 +909,37 @@ fn draw_input_popup(frame: &mut Frame, app: &App, title: &str) {
                 let ghost_part = &suggestion[typed_part.len()..];
                 input_spans.push(Span::styled(
                     ghost_part,
-                    Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+                    Style::default()
+                        .fg(theme.dim)
+                        .add_modifier(Modifier::ITALIC),
                 ));
             }
         }
     }
 
-    input_spans.push(Span::styled("│", Style::default().fg(Color::Yellow)));
+    input_spans.push(Span::styled("│", Style::default().fg(theme.highlight)));
 
     let input = Paragraph::new(Line::from(input_spans));
     frame.render_widget(input, chunks[1]);
 
     // Recent tags row
     let recent_tags = app.recent_tags();
-    if !recent_tags.is_empty() {
-        let mut tag_spans = vec![
-            Span::styled("Recent: ", Style::default().fg(Color::DarkGray)),
-        ];
+    let takes_tags = !matches!(
+        app.input_mode,
+        InputMode::AddingSubtask
+            | InputMode::EditingSubtask
+            | InputMode::NamingProject
+            | InputMode::RenamingTag
+    );
+    if takes_tags && !recent_tags.is_empty() {
+        let mut tag_spans = vec![Span::styled("Recent: ", Style::default().fg(theme.dim))];
         for (i, tag) in recent_tags.iter().take(5).enumerate() {
             if i > 0 {
                 tag_spans.push(Span::raw(" "));
             }
             tag_spans.push(Span::styled(
                 format!("#{}", tag),
-                Style::default().fg(Color::Blue),
+                Style::default().fg(tag_color(app, tag).unwrap_or(theme.info)),
             ));
         }
         let recent = Paragraph::new(Line::from(tag_spans));
@@ -678,60 +953,109 @@ fn draw_input_popup(frame: &mut Frame, app: &App, title: &str) {
         "Enter ▸ save │ Esc ▸ cancel"
     };
     let hint = Paragraph::new(hint_text)
-        .style(Style::default().fg(Color::DarkGray))
+        .style(Style::default().fg(theme.dim))
         .alignment(Alignment::Center);
     frame.render_widget(hint, chunks[3]);
 }
 
 /// Draw the footer with keybinding hints
 fn draw_footer(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let key = |action| format!("[{}]", app.keymap.hint(KeyContext::Timer, action));
+    // Skip does nothing in Timer mode, so offer the length prompt instead
+    let (next_action, next_label) = match app.timer_mode {
+        TimerMode::Pomodoro => (Action::Skip, " Skip  "),
+        TimerMode::Timer(_) => (Action::SetDuration, " Length  "),
+        TimerMode::Stopwatch if app.is_counting_up() => (Action::Skip, " Stop  "),
+        TimerMode::Stopwatch => (Action::Skip, " Skip  "),
+    };
     let hints = if app.active_pane == ActivePane::Tasks {
         Line::from(vec![
-            Span::styled("[a]", Style::default().fg(Color::Green).bold()),
+            Span::styled(
+                key(Action::AddTask),
+                Style::default().fg(theme.success).bold(),
+            ),
             Span::raw(" Add  "),
-            Span::styled("[e]", Style::default().fg(Color::Yellow).bold()),
+            Span::styled(
+                key(Action::AddSubtask),
+                Style::default().fg(theme.success).bold(),
+            ),
+            Span::raw(" Sub  "),
+            Span::styled(
+                key(Action::EditTask),
+                Style::default().fg(theme.highlight).bold(),
+            ),
             Span::raw(" Edit  "),
-            Span::styled("[d]", Style::default().fg(Color::Red).bold()),
+            Span::styled(
+                key(Action::DeleteTask),
+                Style::default().fg(theme.danger).bold(),
+            ),
             Span::raw(" Del  "),
-            Span::styled("[c]", Style::default().fg(Color::Magenta).bold()),
+            Span::styled(
+                key(Action::ClearCompleted),
+                Style::default().fg(theme.secondary).bold(),
+            ),
             Span::raw(" Clear  "),
-            Span::styled("[⏎]", Style::default().fg(Color::Yellow).bold()),
+            Span::styled(
+                key(Action::ToggleDone),
+                Style::default().fg(theme.highlight).bold(),
+            ),
             Span::raw(" Done  "),
-            Span::styled("[f]", Style::default().fg(Color::Cyan).bold()),
+            Span::styled(key(Action::Search), Style::default().fg(theme.info).bold()),
+            Span::raw(" Find  "),
+            Span::styled(
+                key(Action::ToggleFocus),
+                Style::default().fg(theme.accent).bold(),
+            ),
             Span::raw(" Focus  "),
-            Span::styled("[?]", Style::default().fg(Color::White).bold()),
+            Span::styled(key(Action::Help), Style::default().fg(theme.text).bold()),
             Span::raw(" Help"),
         ])
     } else {
         Line::from(vec![
-            Span::styled("[␣]", Style::default().fg(Color::Green).bold()),
+            Span::styled(
+                key(Action::ToggleTimer),
+                Style::default().fg(theme.success).bold(),
+            ),
             Span::raw(" Play  "),
-            Span::styled("[r]", Style::default().fg(Color::Yellow).bold()),
+            Span::styled(
+                key(Action::ResetTimer),
+                Style::default().fg(theme.highlight).bold(),
+            ),
             Span::raw(" Reset  "),
-            Span::styled("[n]", Style::default().fg(Color::Cyan).bold()),
-            Span::raw(" Skip  "),
-            Span::styled("[f]", Style::default().fg(Color::Magenta).bold()),
+            Span::styled(key(next_action), Style::default().fg(theme.accent).bold()),
+            Span::raw(next_label),
+            Span::styled(
+                key(Action::ToggleFocus),
+                Style::default().fg(theme.secondary).bold(),
+            ),
             Span::raw(" Focus  "),
-            Span::styled("[/]", Style::default().fg(Color::Blue).bold()),
+            Span::styled(
+                key(Action::QuickCapture),
+                Style::default().fg(theme.info).bold(),
+            ),
             Span::raw(" Quick  "),
-            Span::styled("[?]", Style::default().fg(Color::White).bold()),
+            Span::styled(key(Action::Help), Style::default().fg(theme.text).bold()),
             Span::raw(" Help"),
         ])
     };
 
     let footer = Paragraph::new(hints)
-        .style(Style::default().fg(Color::White))
+        .style(Style::default().fg(theme.text))
         .alignment(Alignment::Center);
 
     frame.render_widget(footer, area);
 }
 
 /// Draw the help overlay popup
-fn draw_help_overlay(frame: &mut Frame) {
+fn draw_help_overlay(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
     let area = frame.area();
 
+    let help_items = app.keymap.help_entries(KeyContext::Timer);
+
     let popup_width = 40.min(area.width.saturating_sub(4));
-    let popup_height = 20.min(area.height.saturating_sub(4));
+    let popup_height = (help_items.len() as u16 + 2).min(area.height.saturating_sub(4));
 
     let popup_area = Rect {
         x: (area.width.saturating_sub(popup_width)) / 2,
@@ -745,38 +1069,23 @@ fn draw_help_overlay(frame: &mut Frame) {
     let block = Block::default()
         .borders(Borders::ALL)
         .border_set(border::ROUNDED)
-        .border_style(Style::default().fg(Color::Cyan))
+        .border_style(Style::default().fg(theme.accent))
         .title(" ⌨ Shortcuts ")
-        .title_style(Style::default().fg(Color::Cyan).bold());
+        .title_style(Style::default().fg(theme.accent).bold());
 
     let inner_area = block.inner(popup_area);
     frame.render_widget(block, popup_area);
 
-    let help_items = vec![
-        ("Space", "Start / Pause"),
-        ("r", "Reset timer"),
-        ("n", "Skip to next"),
-        ("m", "Toggle mode"),
-        ("f", "Focus mode"),
-        ("Tab", "Switch pane"),
-        ("j / k", "Navigate"),
-        ("a", "Add task"),
-        ("e", "Edit task"),
-        ("d", "Delete task"),
-        ("c", "Clear completed"),
-        ("/", "Quick capture"),
-        ("Enter", "Toggle done"),
-        ("1 2 3", "Switch view"),
-        ("q", "Quit"),
-    ];
-
     let help_lines: Vec<Line> = help_items
         .iter()
         .map(|(key, desc)| {
             Line::from(vec![
-                Span::styled(format!("{:>8}", key), Style::default().fg(Color::Yellow).bold()),
+                Span::styled(
+                    format!("{:>10}", key),
+                    Style::default().fg(theme.highlight).bold(),
+                ),
                 Span::styled("  ", Style::default()),
-                Span::styled(*desc, Style::default().fg(Color::White)),
+                Span::styled(*desc, Style::default().fg(theme.text)),
             ])
         })
         .collect();
@@ -787,8 +1096,474 @@ fn draw_help_overlay(frame: &mut Frame) {
 
 
 
+/// Draw the prompt for phases that finished while the app was closed
+fn draw_resume_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = frame.area();
+
+    let popup_width = 50.min(area.width.saturating_sub(4));
+    let popup_height = (app.missed_phases.len() as u16 + 7).min(area.height.saturating_sub(2));
+
+    let popup_area = Rect {
+        x: (area.width.saturating_sub(popup_width)) / 2,
+        y: (area.height.saturating_sub(popup_height)) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(border::ROUNDED)
+        .border_style(Style::default().fg(theme.accent))
+        .title(" ⏰ While you were away ")
+        .title_style(Style::default().fg(theme.accent).bold());
+
+    let inner_area = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "These phases finished while pomo-tui was closed:",
+            Style::default().fg(theme.muted),
+        )),
+        Line::from(""),
+    ];
+    for phase in &app.missed_phases {
+        let ended = phase.ended_at.with_timezone(&chrono::Local).format("%H:%M");
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {} ", phase.state.display_name()),
+                Style::default().fg(theme.state_color(phase.state)),
+            ),
+            Span::styled(
+                format!("{} min, ended {}", phase.duration_secs / 60, ended),
+                Style::default().fg(theme.dim),
+            ),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Enter ▸ record them │ Esc ▸ discard",
+        Style::default().fg(theme.dim),
+    )));
+
+    let prompt = Paragraph::new(lines).alignment(Alignment::Center);
+    frame.render_widget(prompt, inner_area);
+}
+
+/// Draw the prompt for a custom Timer-mode length
+fn draw_duration_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = frame.area();
+
+    let popup_width = 46.min(area.width.saturating_sub(4));
+    let popup_height = 8;
+
+    let popup_area = Rect {
+        x: (area.width.saturating_sub(popup_width)) / 2,
+        y: (area.height.saturating_sub(popup_height)) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(border::ROUNDED)
+        .border_style(Style::default().fg(theme.info))
+        .title(" ⏱ Timer Length ")
+        .title_style(Style::default().fg(theme.info).bold());
+
+    let inner_area = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Prompt
+            Constraint::Length(1), // Input
+            Constraint::Length(1), // Recent durations
+            Constraint::Length(1), // Spacer
+            Constraint::Length(2), // Hints
+        ])
+        .split(inner_area);
+
+    // Prompt turns red while the text isn't a valid duration
+    let valid = parse_duration(&app.input_buffer).is_some();
+    let (prompt_text, prompt_color) = if app.input_buffer.is_empty() || valid {
+        ("Minutes, or e.g. 1h30m, 90s:", theme.dim)
+    } else {
+        ("Use 45, 1h30m or 90s (1 min – 3 h)", theme.danger)
+    };
+    frame.render_widget(
+        Paragraph::new(prompt_text).style(Style::default().fg(prompt_color)),
+        chunks[0],
+    );
+
+    let input = Paragraph::new(Line::from(vec![
+        Span::styled(&app.input_buffer, Style::default().fg(theme.text)),
+        Span::styled("│", Style::default().fg(theme.info)),
+    ]));
+    frame.render_widget(input, chunks[1]);
+
+    if !app.config.recent_timer_secs.is_empty() {
+        let mut recent_spans = vec![Span::styled("Recent: ", Style::default().fg(theme.dim))];
+        for (i, secs) in app.config.recent_timer_secs.iter().enumerate() {
+            if i > 0 {
+                recent_spans.push(Span::raw(" "));
+            }
+            recent_spans.push(Span::styled(
+                format_duration_input(*secs),
+                Style::default().fg(theme.highlight),
+            ));
+        }
+        frame.render_widget(Paragraph::new(Line::from(recent_spans)), chunks[2]);
+    }
+
+    let hint_text = if app.config.recent_timer_secs.is_empty() {
+        "Enter ▸ set │ Esc ▸ cancel"
+    } else {
+        "Tab ▸ recent │ Enter ▸ set │ Esc ▸ cancel"
+    };
+    let hint = Paragraph::new(hint_text)
+        .style(Style::default().fg(theme.dim))
+        .alignment(Alignment::Center);
+    frame.render_widget(hint, chunks[4]);
+}
+
+/// Draw the profile picker
+fn draw_profile_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = frame.area();
+
+    let popup_width = 40.min(area.width.saturating_sub(4));
+    let popup_height = (app.config.profiles.len() as u16 + 4).min(area.height.saturating_sub(2));
+
+    let popup_area = Rect {
+        x: (area.width.saturating_sub(popup_width)) / 2,
+        y: (area.height.saturating_sub(popup_height)) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(border::ROUNDED)
+        .border_style(Style::default().fg(theme.accent))
+        .title(" ◆ Profiles ")
+        .title_style(Style::default().fg(theme.accent).bold());
+
+    let inner_area = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let mut lines: Vec<Line> = app
+        .config
+        .profiles
+        .iter()
+        .enumerate()
+        .map(|(i, profile)| {
+            let selected = i == app.profile_cursor;
+            let active = profile.name == app.config.active_profile;
+            let pointer = if selected { "▸ " } else { "  " };
+            let marker = if active { " ●" } else { "" };
+            let name_style = if selected {
+                Style::default().fg(theme.highlight).bold()
+            } else {
+                Style::default().fg(theme.text)
+            };
+            Line::from(vec![
+                Span::styled(pointer, Style::default().fg(theme.highlight)),
+                Span::styled(format!("{:<14}", profile.name), name_style),
+                Span::styled(profile.summary(), Style::default().fg(theme.dim)),
+                Span::styled(marker, Style::default().fg(theme.success)),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(
+        Line::from(Span::styled(
+            "Enter ▸ use │ Esc ▸ cancel",
+            Style::default().fg(theme.dim),
+        ))
+        .alignment(Alignment::Center),
+    );
+
+    frame.render_widget(Paragraph::new(lines), inner_area);
+}
+
+/// Draw the task list picker
+fn draw_project_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = frame.area();
+
+    let popup_width = 40.min(area.width.saturating_sub(4));
+    let popup_height = (app.projects.len() as u16 + 4).min(area.height.saturating_sub(2));
+
+    let popup_area = Rect {
+        x: (area.width.saturating_sub(popup_width)) / 2,
+        y: (area.height.saturating_sub(popup_height)) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(border::ROUNDED)
+        .border_style(Style::default().fg(theme.secondary))
+        .title(" 📋 Task Lists ")
+        .title_style(Style::default().fg(theme.secondary).bold());
+
+    let inner_area = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let mut lines: Vec<Line> = app
+        .projects
+        .iter()
+        .enumerate()
+        .map(|(i, project)| {
+            let selected = i == app.project_cursor;
+            let active = *project == app.active_project;
+            let open = app
+                .tasks
+                .iter()
+                .filter(|t| t.project == *project && !t.completed)
+                .count();
+            let pointer = if selected { "▸ " } else { "  " };
+            let marker = if active { " ●" } else { "" };
+            let name_style = if selected {
+                Style::default().fg(theme.highlight).bold()
+            } else {
+                Style::default().fg(theme.text)
+            };
+            Line::from(vec![
+                Span::styled(pointer, Style::default().fg(theme.highlight)),
+                Span::styled(format!("{:<20}", project), name_style),
+                Span::styled(format!("{} open", open), Style::default().fg(theme.dim)),
+                Span::styled(marker, Style::default().fg(theme.success)),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(
+        Line::from(Span::styled(
+            "Enter ▸ open │ n ▸ new │ Esc ▸ cancel",
+            Style::default().fg(theme.dim),
+        ))
+        .alignment(Alignment::Center),
+    );
+
+    frame.render_widget(Paragraph::new(lines), inner_area);
+}
+
+/// Draw the archive of cleared and deleted tasks, newest first
+fn draw_archive_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = frame.area();
+    let entries = &app.archive.entries;
+
+    let popup_width = 64.min(area.width.saturating_sub(4));
+    let popup_height = (entries.len().max(1) as u16 + 4).min(area.height.saturating_sub(2));
+
+    let popup_area = Rect {
+        x: (area.width.saturating_sub(popup_width)) / 2,
+        y: (area.height.saturating_sub(popup_height)) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(border::ROUNDED)
+        .border_style(Style::default().fg(theme.info))
+        .title(format!(" 🗄 Archive ({}) ", entries.len()))
+        .title_style(Style::default().fg(theme.info).bold());
+
+    let inner_area = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(2)])
+        .split(inner_area);
+
+    if entries.is_empty() {
+        let empty = Paragraph::new("Nothing archived yet")
+            .style(Style::default().fg(theme.dim))
+            .alignment(Alignment::Center);
+        frame.render_widget(empty, chunks[0]);
+    } else {
+        // Scroll so the cursor stays in view
+        let rows = chunks[0].height as usize;
+        let first = app.archive_cursor.saturating_sub(rows.saturating_sub(1));
+        let lines: Vec<Line> = entries
+            .iter()
+            .rev()
+            .enumerate()
+            .skip(first)
+            .take(rows)
+            .map(|(i, entry)| {
+                let selected = i == app.archive_cursor;
+                let task = &entry.task;
+                let pointer = if selected { "▸ " } else { "  " };
+                let checkbox = if task.completed { "✓ " } else { "○ " };
+                let name_style = if selected {
+                    Style::default().fg(theme.highlight).bold()
+                } else {
+                    Style::default().fg(theme.text)
+                };
+                let name = if task.name.chars().count() > 28 {
+                    format!("{}…", task.name.chars().take(27).collect::<String>())
+                } else {
+                    task.name.clone()
+                };
+                let pomodoros = match task.total_pomodoros() {
+                    0 => String::new(),
+                    n => format!("🍅×{} ", n),
+                };
+                Line::from(vec![
+                    Span::styled(pointer, Style::default().fg(theme.highlight)),
+                    Span::styled(checkbox, Style::default().fg(theme.dim)),
+                    Span::styled(format!("{:<29}", name), name_style),
+                    Span::styled(pomodoros, Style::default().fg(theme.danger)),
+                    Span::styled(
+                        format!(
+                            "{} {}",
+                            entry.reason.label(),
+                            entry
+                                .archived_at
+                                .with_timezone(&chrono::Local)
+                                .format("%m-%d")
+                        ),
+                        Style::default().fg(theme.dim),
+                    ),
+                ])
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), chunks[0]);
+    }
+
+    let hint = Paragraph::new("Enter ▸ restore │ x ▸ forget │ Esc ▸ close")
+        .style(Style::default().fg(theme.dim))
+        .alignment(Alignment::Center);
+    frame.render_widget(hint, chunks[1]);
+}
+
+/// A tag's color from the tag manager, inherited from its parents
+fn tag_color(app: &App, tag: &str) -> Option<Color> {
+    app.tag_store
+        .color_of(tag)
+        .and_then(|color| color.parse().ok())
+}
+
+/// Draw the tag manager: every tag as a tree, with its color and how many tasks use it
+fn draw_tag_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = frame.area();
+    let names = app.tag_names();
+
+    let popup_width = 56.min(area.width.saturating_sub(4));
+    let popup_height = (names.len().max(1) as u16 + 4).min(area.height.saturating_sub(2));
+
+    let popup_area = Rect {
+        x: (area.width.saturating_sub(popup_width)) / 2,
+        y: (area.height.saturating_sub(popup_height)) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(border::ROUNDED)
+        .border_style(Style::default().fg(theme.info))
+        .title(format!(" # Tags ({}) ", names.len()))
+        .title_style(Style::default().fg(theme.info).bold());
+
+    let inner_area = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(2)])
+        .split(inner_area);
+
+    if names.is_empty() {
+        let empty = Paragraph::new("No tags yet (add one with #tag)")
+            .style(Style::default().fg(theme.dim))
+            .alignment(Alignment::Center);
+        frame.render_widget(empty, chunks[0]);
+    } else {
+        // Scroll so the cursor stays in view
+        let rows = chunks[0].height as usize;
+        let first = app.tag_cursor.saturating_sub(rows.saturating_sub(1));
+        let lines: Vec<Line> = names
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(rows)
+            .map(|(i, name)| {
+                let selected = i == app.tag_cursor;
+                let pointer = if selected { "▸ " } else { "  " };
+                // Nested tags are indented under their parent and show only their last part
+                let depth = name.matches('/').count();
+                let leaf = name.rsplit('/').next().unwrap_or(name);
+                let label = format!("{}#{}", "  ".repeat(depth), leaf);
+                let color = tag_color(app, name);
+                let name_style = match (selected, color) {
+                    (true, _) => Style::default().fg(theme.highlight).bold(),
+                    (false, Some(color)) => Style::default().fg(color),
+                    (false, None) => Style::default().fg(theme.text),
+                };
+                let swatch = match color {
+                    Some(color) => Span::styled("● ", Style::default().fg(color)),
+                    None => Span::styled("○ ", Style::default().fg(theme.dim)),
+                };
+                let tasks = app
+                    .tasks
+                    .iter()
+                    .filter(|t| t.tags.iter().any(|tag| tag.eq_ignore_ascii_case(name)))
+                    .count();
+                let own_color = app
+                    .tag_store
+                    .tags
+                    .iter()
+                    .find(|t| t.name.eq_ignore_ascii_case(name))
+                    .and_then(|t| t.color.clone())
+                    .unwrap_or_default();
+                Line::from(vec![
+                    Span::styled(pointer, Style::default().fg(theme.highlight)),
+                    swatch,
+                    Span::styled(format!("{:<28}", label), name_style),
+                    Span::styled(
+                        format!("{:>3} task{} ", tasks, if tasks == 1 { " " } else { "s" }),
+                        Style::default().fg(theme.dim),
+                    ),
+                    Span::styled(own_color, Style::default().fg(theme.dim)),
+                ])
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), chunks[0]);
+    }
+
+    let hint = Paragraph::new("r ▸ rename │ c ▸ color │ x ▸ delete │ Esc ▸ close")
+        .style(Style::default().fg(theme.dim))
+        .alignment(Alignment::Center);
+    frame.render_widget(hint, chunks[1]);
+}
+
 /// Draw celebration overlay with confetti
 fn draw_celebration_overlay(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
     let area = frame.area();
 
     let popup_width = 45.min(area.width.saturating_sub(4));
@@ -814,7 +1589,7 @@ fn draw_celebration_overlay(frame: &mut Frame, app: &App) {
     let block = Block::default()
         .borders(Borders::ALL)
         .border_set(border::DOUBLE)
-        .border_style(Style::default().fg(Color::Yellow));
+        .border_style(Style::default().fg(theme.highlight));
 
     let inner_area = block.inner(popup_area);
     frame.render_widget(block, popup_area);
@@ -822,17 +1597,20 @@ fn draw_celebration_overlay(frame: &mut Frame, app: &App) {
     let celebration_text = vec![
         Line::from(Span::styled(
             &confetti_line,
-            Style::default().fg(Color::Yellow),
+            Style::default().fg(theme.highlight),
         )),
         Line::from(""),
         Line::from(Span::styled(
             &app.celebration_message,
-            Style::default().fg(Color::White).bold().add_modifier(Modifier::SLOW_BLINK),
+            Style::default()
+                .fg(theme.text)
+                .bold()
+                .add_modifier(Modifier::SLOW_BLINK),
         )),
         Line::from(""),
         Line::from(Span::styled(
             &confetti_line,
-            Style::default().fg(Color::Magenta),
+            Style::default().fg(theme.secondary),
         )),
     ];
 